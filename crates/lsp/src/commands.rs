@@ -1,13 +1,25 @@
-use tower_lsp::lsp_types::Command;
+use serde_json::Value;
+use tower_lsp::lsp_types::{Command, Uri};
+
+use crate::wordlists::Layer;
 
 pub const ADD_TO_DICT: &str = "add-to-dict";
 pub struct AddToDict;
 impl AddToDict {
-	pub fn command(word: String) -> Command {
+	pub fn command(word: String, layer: Layer, uri: &Uri) -> Command {
 		Command {
-			title: "Add a word to the workspace dictionary".into(),
+			title: format!("Add a word to the {layer} word list"),
 			command: ADD_TO_DICT.into(),
-			arguments: Some(vec![word.into()]),
+			arguments: Some(vec![
+				word.into(),
+				serde_json::json!(layer),
+				serde_json::json!(uri),
+			]),
 		}
 	}
+
+	/// Word, target layer and document the command was issued from
+	pub fn arguments(arguments: Vec<Value>) -> serde_json::Result<(String, Layer, Uri)> {
+		serde_json::from_value(Value::Array(arguments))
+	}
 }
@@ -0,0 +1,48 @@
+use lspelling_wordc::checker::Options;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{dictionary::DEFAULT_DICTIONARY, exclude::ExcludeOptions};
+
+/// Server settings, read from `initializationOptions` and updated on
+/// `workspace/didChangeConfiguration`
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Config {
+	/// Hunspell dictionaries to load, either names (e.g. `en_US`) or paths
+	/// without extension, a word is accepted if any of them knows it
+	pub(crate) dictionaries: Vec<String>,
+
+	/// Accept language keywords, standard library names and common programming terms
+	pub(crate) builtin_vocabulary: bool,
+	/// Accept crate names declared in the workspace `Cargo.toml` and `Cargo.lock` files
+	pub(crate) project_vocabulary: bool,
+
+	#[serde(flatten)]
+	pub(crate) exclude: ExcludeOptions,
+
+	#[serde(flatten)]
+	pub(crate) checker: Options,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			dictionaries: vec![DEFAULT_DICTIONARY.into()],
+			builtin_vocabulary: true,
+			project_vocabulary: true,
+			exclude: ExcludeOptions::default(),
+			checker: Options::default(),
+		}
+	}
+}
+
+impl Config {
+	/// Settings can either be sent as is or namespaced under `lspelling`
+	pub(crate) fn from_settings(mut settings: Value) -> serde_json::Result<Self> {
+		if let Some(namespaced) = settings.get_mut("lspelling") {
+			settings = namespaced.take();
+		}
+		serde_json::from_value(settings)
+	}
+}
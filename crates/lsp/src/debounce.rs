@@ -1,5 +1,7 @@
-use lspelling_wordc::{checker::Checker, span::Source};
-use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent, TextDocumentItem};
+use lspelling_wordc::{checker::Checker, directive, span::Source};
+use tower_lsp::lsp_types::{
+	Position, Range, TextDocumentContentChangeEvent, TextDocumentItem, TextEdit,
+};
 
 // TODO: wtf is this module
 
@@ -10,6 +12,9 @@ pub(crate) struct CheckedDocument {
 	pub(crate) source: Source,
 
 	pub(crate) checker: Checker<'static>,
+
+	/// Matched by an ignored path, in which case it is not checked
+	pub(crate) path_ignored: bool,
 }
 
 impl CheckedDocument {
@@ -36,6 +41,33 @@ impl CheckedDocument {
 		// 	todo!()
 		// }
 	}
+
+	/// Inserts a directive accepting `word` in the whole document, after the shebang if any
+	pub(crate) fn ignore_word_edit(&self, word: &str) -> TextEdit {
+		let line = u32::from(self.source.0.line(0).to_string().starts_with("#!"));
+		let comment = directive::comment(&self.item.language_id, &format!("ignore {word}"));
+
+		let start = Position::new(line, 0);
+		TextEdit::new(Range::new(start, start), format!("{comment}\n"))
+	}
+
+	/// Inserts a directive skipping `line`, indented like it
+	pub(crate) fn ignore_line_edit(&self, line: u32) -> TextEdit {
+		let indent = self
+			.source
+			.0
+			.get_line(line as usize)
+			.map(|line| {
+				line.chars()
+					.take_while(|c| matches!(c, ' ' | '\t'))
+					.collect::<String>()
+			})
+			.unwrap_or_default();
+		let comment = directive::comment(&self.item.language_id, "ignore-next-line");
+
+		let start = Position::new(line, 0);
+		TextEdit::new(Range::new(start, start), format!("{indent}{comment}\n"))
+	}
 }
 
 pub(crate) trait ToLspType: Sized {
@@ -0,0 +1,89 @@
+use lspelling_wordc::backend::Hunspell;
+use ruspell::Dictionary;
+use std::{
+	env,
+	ffi::OsStr,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Dictionary bundled at build time
+pub(crate) const DEFAULT_DICTIONARY: &str = env!("HUNSPELL_DICT");
+
+/// Usual locations of hunspell dictionaries
+const SYSTEM_DIRS: [&str; 3] = [
+	"/usr/share/hunspell",
+	"/usr/local/share/hunspell",
+	"/usr/share/myspell",
+];
+
+/// Loads a hunspell dictionary from a name (e.g. `en_US`) or a path without extension
+pub(crate) fn load(name: &str) -> Option<Hunspell> {
+	let Some(pair) = resolve(name) else {
+		tracing::warn!("could not find a dictionary named `{name}`");
+		return None;
+	};
+
+	match Dictionary::from_pair(&pair) {
+		Ok(dictionary) => {
+			let name = pair.file_name().map_or_else(
+				|| name.to_owned(),
+				|name| name.to_string_lossy().into_owned(),
+			);
+			let hunspell = Hunspell::new(name, dictionary);
+			// Dictionaries in legacy encodings are loaded without case-insensitive entries
+			let dic = pair.with_extension("dic");
+			Some(match fs::read_to_string(&dic) {
+				Ok(content) => hunspell.with_mixed_case(&content),
+				Err(err) => {
+					tracing::debug!("could not read `{}`: {err}", dic.display());
+					hunspell
+				}
+			})
+		}
+		Err(err) => {
+			tracing::warn!("could not load dictionary `{}`: {err:?}", pair.display());
+			None
+		}
+	}
+}
+
+/// The `.dic` and `.aff` files of a dictionary
+pub(crate) fn files(name: &str) -> Vec<PathBuf> {
+	resolve(name).map_or_else(Vec::new, |pair| {
+		vec![pair.with_extension("dic"), pair.with_extension("aff")]
+	})
+}
+
+/// Whether `path` is the `.dic` or `.aff` file of one of the dictionaries `names`
+pub(crate) fn is_dictionary_file(path: &Path, names: &[String]) -> bool {
+	matches!(
+		path.extension().and_then(OsStr::to_str),
+		Some("dic" | "aff")
+	) && names
+		.iter()
+		.any(|name| files(name).iter().any(|file| file == path))
+}
+
+/// Finds the `.dic` and `.aff` pair of a dictionary, returned without extension
+fn resolve(name: &str) -> Option<PathBuf> {
+	let path = Path::new(name);
+	if path.is_absolute() {
+		return Some(path.to_owned());
+	}
+
+	search_dirs()
+		.into_iter()
+		.map(|dir| dir.join(name))
+		.find(|pair| pair.with_extension("dic").is_file())
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+	let mut dirs = Vec::new();
+	if let Some(paths) = env::var_os("DICPATH") {
+		dirs.extend(env::split_paths(&paths));
+	}
+	dirs.extend(Path::new(DEFAULT_DICTIONARY).parent().map(Path::to_owned));
+	dirs.extend(SYSTEM_DIRS.iter().map(PathBuf::from));
+	dirs
+}
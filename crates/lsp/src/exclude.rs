@@ -0,0 +1,127 @@
+//! Documents that are not checked: ignored paths, generated and large files
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use lspelling_wordc::{directive::COMMENT_MARKERS, span::Source};
+use serde::Deserialize;
+use std::{fmt, path::Path};
+
+/// Markers of generated files, looked for in the comments of the first lines
+const GENERATED_MARKERS: [&str; 3] = ["@generated", "DO NOT EDIT", "automatically generated"];
+/// Number of lines in which generated file markers are looked for
+const HEADER_LINES: usize = 5;
+
+const DEFAULT_IGNORE_PATHS: [&str; 6] = [
+	"target/",
+	"vendor/",
+	"node_modules/",
+	"Cargo.lock",
+	"*.min.js",
+	"*.min.css",
+];
+
+/// Settings deciding which documents are checked
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct ExcludeOptions {
+	/// Files that are not checked, in the `.gitignore` syntax relative to the workspace folder
+	pub(crate) ignore_paths: Vec<String>,
+	/// Do not check files ignored by git
+	pub(crate) use_gitignore: bool,
+	/// Do not check files with a `@generated` or `DO NOT EDIT` header comment
+	pub(crate) ignore_generated: bool,
+	/// Size in bytes over which files are not checked
+	pub(crate) max_file_size: usize,
+}
+
+impl Default for ExcludeOptions {
+	fn default() -> Self {
+		Self {
+			ignore_paths: DEFAULT_IGNORE_PATHS.map(Into::into).to_vec(),
+			use_gitignore: true,
+			ignore_generated: true,
+			max_file_size: 1024 * 1024,
+		}
+	}
+}
+
+/// Why a document is not checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Exclusion {
+	/// Matched by an ignored path or a `.gitignore`
+	Path,
+	/// Larger than the maximum file size, in bytes
+	TooLarge(usize),
+	Generated,
+}
+
+impl fmt::Display for Exclusion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Path => f.write_str("its path is ignored"),
+			Self::TooLarge(size) => write!(f, "it is too large ({size} bytes)"),
+			Self::Generated => f.write_str("it is generated"),
+		}
+	}
+}
+
+/// Whether `path`, which must be inside `root`, is matched by one of `patterns`
+/// or, if `gitignore` is set, by a `.gitignore` between it and `root`
+///
+/// Patterns follow the `.gitignore` syntax and are relative to `root`.
+pub(crate) fn is_path_ignored<'p>(
+	path: &Path,
+	root: &Path,
+	patterns: impl IntoIterator<Item = &'p str>,
+	gitignore: bool,
+) -> bool {
+	let mut builder = GitignoreBuilder::new(root);
+	for pattern in patterns {
+		if let Err(err) = builder.add_line(None, pattern) {
+			tracing::warn!("invalid ignored path `{pattern}`: {err}");
+		}
+	}
+	let ignored = match builder.build() {
+		Ok(matcher) => matcher.matched_path_or_any_parents(path, false).is_ignore(),
+		Err(err) => {
+			tracing::warn!("could not build ignored paths: {err}");
+			false
+		}
+	};
+	if ignored || !gitignore {
+		return ignored;
+	}
+
+	// Nested `.gitignore`s take precedence over the ones of parent directories
+	path.ancestors()
+		.skip(1)
+		.take_while(|dir| dir.starts_with(root))
+		.map(|dir| dir.join(".gitignore"))
+		.filter(|file| file.is_file())
+		.find_map(|file| {
+			let (matcher, err) = Gitignore::new(&file);
+			if let Some(err) = err {
+				tracing::warn!("could not read `{}`: {err}", file.display());
+			}
+			let verdict = matcher.matched_path_or_any_parents(path, false);
+			(!verdict.is_none()).then(|| verdict.is_ignore())
+		})
+		.unwrap_or(false)
+}
+
+/// Exclusion based on the content of a document
+pub(crate) fn content_exclusion(source: &Source, options: &ExcludeOptions) -> Option<Exclusion> {
+	let size = source.0.len_bytes();
+	if size > options.max_file_size {
+		return Some(Exclusion::TooLarge(size));
+	}
+
+	let generated = source.0.lines().take(HEADER_LINES).any(|line| {
+		let line = line.to_string();
+		let line = line.trim_start();
+		COMMENT_MARKERS
+			.iter()
+			.any(|marker| line.starts_with(marker))
+			&& GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+	});
+	(options.ignore_generated && generated).then_some(Exclusion::Generated)
+}
@@ -0,0 +1,328 @@
+//! Words and ignore rules read from the configuration of other spell checkers:
+//! cspell, codespell and typos
+
+use lspelling_wordc::wordlist::WordList;
+use serde::Deserialize;
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use crate::workspace::{read, Workspace};
+
+const CSPELL_FILES: [&str; 3] = ["cspell.json", ".cspell.json", "cspell.config.json"];
+const CODESPELL_FILES: [&str; 2] = [".codespellrc", "setup.cfg"];
+const TYPOS_FILES: [&str; 3] = ["typos.toml", "_typos.toml", ".typos.toml"];
+
+/// Configuration imported from a workspace folder
+#[derive(Debug, Default)]
+pub(crate) struct Imported {
+	/// Workspace folder the configuration files were found in
+	pub(crate) root: PathBuf,
+	pub(crate) words: Arc<WordList>,
+	/// Globs of files that are not checked, relative to `root`
+	pub(crate) ignore_paths: Vec<String>,
+	/// Patterns of text that is not checked
+	pub(crate) ignore_regexes: Vec<String>,
+}
+
+impl Imported {
+	/// Reads the configuration files at the root of every workspace folder
+	pub(crate) fn load_all(workspace: &Workspace) -> Vec<Self> {
+		workspace
+			.roots()
+			.iter()
+			.map(|root| Self::load(root))
+			.collect()
+	}
+
+	fn load(root: &Path) -> Self {
+		let mut imported = Self {
+			root: root.to_owned(),
+			..Self::default()
+		};
+		let mut words = WordList::default();
+
+		for name in CSPELL_FILES {
+			if let Some(content) = read(&root.join(name)) {
+				match parse_jsonc::<CSpell>(&content) {
+					Ok(cspell) => imported.add_cspell(&mut words, cspell),
+					Err(err) => tracing::warn!("could not parse `{name}`: {err}"),
+				}
+			}
+		}
+		for name in CODESPELL_FILES {
+			if let Some(content) = read(&root.join(name)) {
+				imported.add_codespell(&mut words, &ini_section(&content, "codespell"));
+			}
+		}
+		for name in TYPOS_FILES {
+			if let Some(content) = read(&root.join(name)) {
+				match toml::from_str::<Typos>(&content) {
+					Ok(typos) => imported.add_typos(&mut words, typos),
+					Err(err) => tracing::warn!("could not parse `{name}`: {err}"),
+				}
+			}
+		}
+
+		imported.words = Arc::new(words);
+		imported
+	}
+
+	fn add_cspell(&mut self, words: &mut WordList, cspell: CSpell) {
+		words.extend(cspell.words);
+		words.extend(cspell.ignore_words);
+		self.ignore_paths.extend(cspell.ignore_paths);
+
+		// Only literal patterns, e.g. `/0x[0-9a-f]+/gi`, named ones are predefined by cspell
+		for pattern in cspell.ignore_reg_exp_list {
+			let Some((regex, flags)) = pattern
+				.strip_prefix('/')
+				.and_then(|pattern| pattern.rsplit_once('/'))
+			else {
+				tracing::debug!("skipping named cspell pattern `{pattern}`");
+				continue;
+			};
+
+			let flags = flags
+				.chars()
+				.filter(|flag| matches!(flag, 'i' | 'm' | 's' | 'u'))
+				.collect::<String>();
+			self.ignore_regexes.push(if flags.is_empty() {
+				regex.to_owned()
+			} else {
+				format!("(?{flags}){regex}")
+			});
+		}
+	}
+
+	fn add_codespell(&mut self, words: &mut WordList, section: &HashMap<String, String>) {
+		let list = |key: &str| {
+			section
+				.get(key)
+				.into_iter()
+				.flat_map(|value| value.split(','))
+				.map(str::trim)
+				.filter(|item| !item.is_empty())
+				.map(ToOwned::to_owned)
+				.collect::<Vec<_>>()
+		};
+
+		words.extend(list("ignore-words-list"));
+		for file in list("ignore-words") {
+			if let Some(content) = read(&self.root.join(file)) {
+				words.extend(
+					content
+						.lines()
+						.map(str::trim)
+						.filter(|word| !word.is_empty())
+						.map(ToOwned::to_owned),
+				);
+			}
+		}
+		// Paths are relative to the root, where codespell is usually run as `./path`
+		self.ignore_paths
+			.extend(list("skip").into_iter().map(|path| {
+				path.strip_prefix("./")
+					.map_or_else(|| path.clone(), ToOwned::to_owned)
+			}));
+		self.ignore_regexes
+			.extend(section.get("ignore-regex").cloned());
+	}
+
+	fn add_typos(&mut self, words: &mut WordList, typos: Typos) {
+		// Entries mapping a word to itself accept it, others are corrections
+		let accepted = [typos.default.words, typos.default.identifiers]
+			.into_iter()
+			.flatten()
+			.filter(|(word, correction)| word == correction)
+			.map(|(word, _)| word);
+		words.extend(accepted);
+
+		self.ignore_paths.extend(typos.files.extend_exclude);
+		self.ignore_regexes.extend(typos.default.ignore_re);
+		self.ignore_regexes
+			.extend(typos.default.ignore_identifiers_re);
+	}
+}
+
+/// Whether `path` is a configuration file imported from another spell checker
+pub(crate) fn is_imported_config(path: &Path) -> bool {
+	path.file_name()
+		.and_then(|name| name.to_str())
+		.is_some_and(|name| {
+			CSPELL_FILES.contains(&name)
+				|| CODESPELL_FILES.contains(&name)
+				|| TYPOS_FILES.contains(&name)
+		})
+}
+
+/// Names of every imported configuration file
+pub(crate) fn config_files() -> impl Iterator<Item = &'static str> {
+	CSPELL_FILES
+		.into_iter()
+		.chain(CODESPELL_FILES)
+		.chain(TYPOS_FILES)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CSpell {
+	words: Vec<String>,
+	ignore_words: Vec<String>,
+	ignore_paths: Vec<String>,
+	ignore_reg_exp_list: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Typos {
+	default: TyposDefault,
+	files: TyposFiles,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TyposDefault {
+	#[serde(rename = "extend-words")]
+	words: HashMap<String, String>,
+	#[serde(rename = "extend-identifiers")]
+	identifiers: HashMap<String, String>,
+	#[serde(rename = "extend-ignore-re")]
+	ignore_re: Vec<String>,
+	#[serde(rename = "extend-ignore-identifiers-re")]
+	ignore_identifiers_re: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct TyposFiles {
+	extend_exclude: Vec<String>,
+}
+
+/// Parses JSON that may contain comments, as cspell allows
+fn parse_jsonc<T: for<'de> Deserialize<'de>>(content: &str) -> serde_json::Result<T> {
+	let mut json = String::with_capacity(content.len());
+	let mut chars = content.chars().peekable();
+	let mut in_string = false;
+
+	while let Some(c) = chars.next() {
+		match c {
+			'"' => in_string = !in_string,
+			'\\' if in_string => {
+				json.push(c);
+				json.extend(chars.next());
+				continue;
+			}
+			'/' if !in_string && chars.peek() == Some(&'/') => {
+				while chars.next_if(|&c| c != '\n').is_some() {}
+				continue;
+			}
+			'/' if !in_string && chars.peek() == Some(&'*') => {
+				chars.next();
+				let mut previous = ' ';
+				for c in chars.by_ref() {
+					if previous == '*' && c == '/' {
+						break;
+					}
+					previous = c;
+				}
+				continue;
+			}
+			_ => {}
+		}
+		json.push(c);
+	}
+
+	serde_json::from_str(&json)
+}
+
+/// Keys and values of an INI section, with `_` in keys read as `-`
+fn ini_section(content: &str, name: &str) -> HashMap<String, String> {
+	let mut section = HashMap::<String, String>::new();
+	let mut current = None;
+	let mut last_key: Option<String> = None;
+
+	for line in content.lines() {
+		let trimmed = line.trim();
+		if trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+			continue;
+		}
+
+		if let Some(header) = trimmed
+			.strip_prefix('[')
+			.and_then(|header| header.strip_suffix(']'))
+		{
+			current = Some(header.trim().to_owned());
+			last_key = None;
+			continue;
+		}
+		if current.as_deref() != Some(name) {
+			continue;
+		}
+
+		// Indented lines continue the previous value
+		let continued = last_key
+			.as_ref()
+			.filter(|_| line.starts_with(char::is_whitespace))
+			.and_then(|key| section.get_mut(key));
+		if let Some(value) = continued {
+			value.push(',');
+			value.push_str(trimmed);
+		} else if let Some((key, value)) = trimmed.split_once(['=', ':']) {
+			let key = key.trim().replace('_', "-");
+			section.insert(key.clone(), value.trim().to_owned());
+			last_key = Some(key);
+		}
+	}
+
+	section
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_jsonc_skipping_comments() {
+		let content = r#"{
+			// Words of the project
+			"words": ["lspelling", "http://example.com"], /* trailing */
+			"ignoreWords": ["a\"//b"]
+		}"#;
+		let cspell = parse_jsonc::<CSpell>(content).unwrap();
+		assert_eq!(cspell.words, ["lspelling", "http://example.com"]);
+		assert_eq!(cspell.ignore_words, ["a\"//b"]);
+	}
+
+	#[test]
+	fn ini_section_with_continuations() {
+		let content =
+			"[other]\nskip = other\n[codespell]\nignore_words_list = foo,\n  bar\nskip: ./target\n";
+		let section = ini_section(content, "codespell");
+		assert_eq!(section.len(), 2);
+		assert_eq!(section["skip"], "./target");
+
+		let mut words = WordList::default();
+		let mut imported = Imported::default();
+		imported.add_codespell(&mut words, &section);
+		assert!(words.contains("foo") && words.contains("bar"));
+		assert_eq!(imported.ignore_paths, ["target"]);
+	}
+
+	#[test]
+	fn add_typos_accepts_self_mapped_words() {
+		let typos = toml::from_str::<Typos>(
+			"[default.extend-words]\nlspelling = \"lspelling\"\nteh = \"the\"\n\
+			 [default.extend-identifiers]\nfoo_bar = \"foo_bar\"\n",
+		)
+		.unwrap();
+		let mut words = WordList::default();
+		Imported::default().add_typos(&mut words, typos);
+		assert!(words.contains("lspelling"));
+		assert!(words.contains("foo_bar"));
+		assert!(!words.contains("teh"));
+		assert!(!words.contains("the"));
+	}
+}
@@ -1,56 +1,379 @@
 //! LSP
 
-use lspelling_wordc::{checker::Checker, span::Source};
-use ruspell::Dictionary;
+use lspelling_wordc::{
+	backend::{Composite, Hunspell, SpellBackend},
+	checker::{Checker, DiagnosticKind},
+	span::Source,
+	vocabulary,
+	wordlist::WordList,
+};
+use parking_lot::RwLock as SyncRwLock;
 use serde_json::Value;
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	panic::{self, PanicInfo},
 	path::Path,
+	sync::Arc,
 };
 use tokio::{sync::RwLock, time::Instant};
-use tower_lsp::{jsonrpc::Result, lsp_types::*, Client, LanguageServer, LspService, Server};
+use tower_lsp::{
+	jsonrpc, jsonrpc::Result, lsp_types::*, Client, LanguageServer, LspService, Server,
+};
 use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
 
 mod commands;
+mod config;
 mod debounce;
+mod dictionary;
+mod exclude;
+mod imported;
+mod wordlists;
+mod workspace;
 
 use crate::commands::{AddToDict, ADD_TO_DICT};
+use crate::config::Config;
 use crate::debounce::{CheckedDocument, ToLspType as _};
+use crate::exclude::Exclusion;
+use crate::imported::{is_imported_config, Imported};
+use crate::wordlists::{Layer, WordLists, WORDS_FILE};
+use crate::workspace::{is_cargo_file, uri_to_path, Workspace};
+
+/// Id of the file watchers registration, to replace it later on
+const WATCHED_FILES_REGISTRATION: &str = "lspelling-watched-files";
+/// Notification sent by the client when a watched file changes
+const WATCHED_FILES_METHOD: &str = "workspace/didChangeWatchedFiles";
+
+/// Code of diagnostics of words missing from dictionaries
+const MISSPELLED_CODE: i32 = 1;
+/// Code of diagnostics of words written twice in a row
+const REPEATED_CODE: i32 = 2;
+/// Code of diagnostics of common misspellings, which are fixed automatically
+const TYPO_CODE: i32 = 3;
+
+/// Source action applying the correction of every common misspelling
+const FIX_ALL_KIND: CodeActionKind = CodeActionKind::new("source.fixAll.lspelling");
 
 #[derive(Debug)]
 struct Backend {
 	client: Client,
 
-	dictionary: Dictionary,
+	config: SyncRwLock<Config>,
+	workspace: SyncRwLock<Workspace>,
+	dictionaries: SyncRwLock<Vec<Arc<Hunspell>>>,
+	/// Crate names of the workspace Cargo files
+	project_words: SyncRwLock<Arc<WordList>>,
+	/// Crates of the workspace's Cargo manifests and their dependencies
+	project_crates: SyncRwLock<Vec<String>>,
+	/// User, workspace and per-directory word lists
+	word_lists: SyncRwLock<WordLists>,
+	/// Configuration of other spell checkers found in workspace folders
+	imported: SyncRwLock<Vec<Imported>>,
 	documents: RwLock<HashMap<Uri, CheckedDocument>>,
 }
 
 impl Backend {
 	fn new(client: Client) -> Self {
-		let dictionary = Dictionary::from_pair(Path::new(env!("HUNSPELL_DICT"))).unwrap();
-
 		Self {
 			client,
-			dictionary,
+			config: SyncRwLock::default(),
+			workspace: SyncRwLock::default(),
+			dictionaries: SyncRwLock::default(),
+			project_words: SyncRwLock::default(),
+			project_crates: SyncRwLock::default(),
+			word_lists: SyncRwLock::default(),
+			imported: SyncRwLock::default(),
 			documents: RwLock::default(),
 		}
 	}
 
+	/// Applies the current configuration to a document's checker
+	fn configure(&self, checker: &mut Checker, uri: &Uri) {
+		let path = uri_to_path(uri);
+
+		let mut options = self.config.read().checker.clone();
+		options.ignore_regexes.extend(
+			self.imported
+				.read()
+				.iter()
+				.filter(|imported| {
+					path.as_ref()
+						.is_some_and(|path| path.starts_with(&imported.root))
+				})
+				.flat_map(|imported| imported.ignore_regexes.iter().cloned()),
+		);
+		if options.ignore_imports {
+			options.crates.clone_from(&self.project_crates.read());
+		}
+		checker.set_options(options);
+		checker.set_backend(self.backend(checker.language(), path.as_deref()));
+	}
+
+	/// Vocabularies, word lists and dictionaries checking a document in
+	/// `language` located at `path`
+	fn backend(&self, language: &str, path: Option<&Path>) -> Box<dyn SpellBackend> {
+		let (builtin_vocabulary, project_vocabulary) = {
+			let config = self.config.read();
+			(config.builtin_vocabulary, config.project_vocabulary)
+		};
+
+		let mut backend = Composite::default();
+		if builtin_vocabulary {
+			backend.push(vocabulary::programming());
+			if let Some(words) = vocabulary::language(language) {
+				backend.push(words);
+			}
+		}
+		if project_vocabulary {
+			backend.push(Arc::clone(&self.project_words.read()));
+		}
+		let word_lists = self.word_lists.read().for_path(path);
+		for words in word_lists {
+			backend.push(words);
+		}
+		for imported in self.imported.read().iter() {
+			if path.is_some_and(|path| path.starts_with(&imported.root)) {
+				backend.push(Arc::clone(&imported.words));
+			}
+		}
+		for dictionary in self.dictionaries.read().iter() {
+			backend.push(Arc::clone(dictionary));
+		}
+		Box::new(backend)
+	}
+
+	async fn reload_dictionaries(&self) {
+		let names = self.config.read().dictionaries.clone();
+		let count = names.len();
+		let Some(dictionaries) = blocking(move || {
+			names
+				.iter()
+				.filter_map(|name| dictionary::load(name))
+				.map(Arc::new)
+				.collect::<Vec<_>>()
+		})
+		.await
+		else {
+			return;
+		};
+
+		tracing::debug!("loaded {} dictionaries out of {count}", dictionaries.len());
+		*self.dictionaries.write() = dictionaries;
+	}
+
+	async fn reload_project_words(&self) {
+		let workspace = self.workspace.read().clone();
+		let Some((words, crates)) = blocking(move || workspace.cargo_vocabulary()).await else {
+			return;
+		};
+		tracing::debug!("loaded {} project words", words.len());
+		*self.project_words.write() = Arc::new(words);
+		*self.project_crates.write() = crates;
+	}
+
+	async fn reload_word_lists(&self) {
+		let workspace = self.workspace.read().clone();
+		let Some(word_lists) = blocking(move || WordLists::load(&workspace)).await else {
+			return;
+		};
+		tracing::debug!("loaded {} words from word lists", word_lists.len());
+		*self.word_lists.write() = word_lists;
+	}
+
+	async fn reload_imported(&self) {
+		let workspace = self.workspace.read().clone();
+		let Some(imported) = blocking(move || Imported::load_all(&workspace)).await else {
+			return;
+		};
+		for Imported {
+			root,
+			words,
+			ignore_paths,
+			ignore_regexes,
+		} in &imported
+		{
+			tracing::debug!(
+				"imported {} words, {} ignored paths and {} ignored patterns in `{}`",
+				words.len(),
+				ignore_paths.len(),
+				ignore_regexes.len(),
+				root.display()
+			);
+		}
+		*self.imported.write() = imported;
+	}
+
+	/// Whether the document at `uri` is matched by an ignored path of the
+	/// configuration, of an imported one or of a `.gitignore`
+	fn is_path_ignored(&self, uri: &Uri) -> bool {
+		let Some(path) = uri_to_path(uri) else {
+			return false;
+		};
+		let Some(root) = self.workspace.read().root_of(&path).map(Path::to_owned) else {
+			return false;
+		};
+
+		let (mut patterns, use_gitignore) = {
+			let config = self.config.read();
+			(
+				config.exclude.ignore_paths.clone(),
+				config.exclude.use_gitignore,
+			)
+		};
+		patterns.extend(
+			self.imported
+				.read()
+				.iter()
+				.filter(|imported| imported.root == root)
+				.flat_map(|imported| imported.ignore_paths.iter().cloned()),
+		);
+		exclude::is_path_ignored(
+			&path,
+			&root,
+			patterns.iter().map(String::as_str),
+			use_gitignore,
+		)
+	}
+
+	/// Why a document is not checked, if it is not
+	fn exclusion(&self, document: &CheckedDocument) -> Option<Exclusion> {
+		if document.path_ignored {
+			return Some(Exclusion::Path);
+		}
+		exclude::content_exclusion(&document.source, &self.config.read().exclude)
+	}
+
+	/// Asks the client to notify us when project files, word lists, imported
+	/// configurations or dictionaries change
+	async fn register_file_watchers(&self) {
+		let mut patterns = vec![
+			"**/Cargo.toml".to_owned(),
+			"**/Cargo.lock".to_owned(),
+			format!("**/{WORDS_FILE}"),
+		];
+		patterns.extend(imported::config_files().map(|name| format!("**/{name}")));
+		patterns.extend(wordlists::user_path().map(|path| path.display().to_string()));
+		patterns.extend(
+			self.config
+				.read()
+				.dictionaries
+				.iter()
+				.flat_map(|name| dictionary::files(name))
+				.map(|path| path.display().to_string()),
+		);
+
+		let watchers = patterns
+			.into_iter()
+			.map(|pattern| FileSystemWatcher {
+				glob_pattern: GlobPattern::String(pattern),
+				kind: None,
+			})
+			.collect();
+		let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+
+		let registration = Registration {
+			id: WATCHED_FILES_REGISTRATION.into(),
+			method: WATCHED_FILES_METHOD.into(),
+			register_options: Some(serde_json::to_value(options).unwrap()),
+		};
+
+		if let Err(err) = self.client.register_capability(vec![registration]).await {
+			tracing::warn!("could not register file watchers: {err}");
+		}
+	}
+
+	/// Replaces the file watchers, e.g. to follow other dictionaries
+	async fn reregister_file_watchers(&self) {
+		let unregistration = Unregistration {
+			id: WATCHED_FILES_REGISTRATION.into(),
+			method: WATCHED_FILES_METHOD.into(),
+		};
+		if let Err(err) = self
+			.client
+			.unregister_capability(vec![unregistration])
+			.await
+		{
+			tracing::warn!("could not unregister file watchers: {err}");
+		}
+		self.register_file_watchers().await;
+	}
+
+	/// Rebuilds every open document's checker state and republishes diagnostics
+	async fn refresh_documents(&self) {
+		let mut documents = self.documents.write().await;
+		for document in documents.values_mut() {
+			self.configure(&mut document.checker, &document.item.uri);
+			document.path_ignored = self.is_path_ignored(&document.item.uri);
+			self.on_change(document).await;
+		}
+	}
+
 	#[tracing::instrument(skip_all)]
 	async fn on_change(&self, document: &CheckedDocument) {
+		if let Some(exclusion) = self.exclusion(document) {
+			tracing::debug!(
+				"not checking `{}` as {exclusion}",
+				document.item.uri.as_str()
+			);
+			self.client
+				.publish_diagnostics(
+					document.item.uri.clone(),
+					Vec::new(),
+					Some(document.item.version),
+				)
+				.await;
+			return;
+		}
+
+		let dictionaries = document.checker.dictionaries();
+		let tried = if dictionaries.is_empty() {
+			String::new()
+		} else {
+			format!(" (tried {})", dictionaries.join(", "))
+		};
+
 		let diagnostics = document
 			.checker
 			.check()
 			.iter()
 			.map(|diag| {
 				let range = document.source.span_to_range(diag.span).unwrap();
+				let (code, message, data) = match &diag.kind {
+					DiagnosticKind::Misspelled => (
+						NumberOrString::Number(MISSPELLED_CODE),
+						format!("`{}` isn't in a loaded dictionary{tried}", diag.word),
+						Some(diag.word.clone().into()),
+					),
+					// The quick fix removes the duplicate along with the space before it
+					DiagnosticKind::Repeated { removal } => {
+						let removal = document.source.span_to_range(*removal).unwrap();
+						(
+							NumberOrString::Number(REPEATED_CODE),
+							format!("`{}` is repeated", diag.word),
+							serde_json::to_value(removal.to_lsp_type()).ok(),
+						)
+					}
+					DiagnosticKind::Typo { correction } => (
+						NumberOrString::Number(TYPO_CODE),
+						format!("`{}` is a common misspelling of `{correction}`", diag.word),
+						Some(correction.clone().into()),
+					),
+					// Rules are told apart by their code, the data holds the fix
+					DiagnosticKind::Grammar {
+						code,
+						message,
+						replacement,
+					} => (
+						NumberOrString::String((*code).to_owned()),
+						message.clone(),
+						replacement.clone().map(Value::from),
+					),
+				};
 				Diagnostic {
 					range: range.to_lsp_type(),
 					severity: Some(DiagnosticSeverity::INFORMATION),
-					code: Some(NumberOrString::Number(1)),
-					message: format!("`{}` isn't in a loaded dictionary", diag.word),
-					data: Some(diag.word.clone().into()),
+					code: Some(code),
+					message,
+					data,
 					..Default::default()
 				}
 			})
@@ -65,6 +388,32 @@ impl Backend {
 			.await;
 	}
 
+	/// Corrections of every common misspelling of a document
+	async fn fix_all_edits(&self, uri: &Uri) -> Vec<TextEdit> {
+		let documents = self.documents.read().await;
+		let Some(document) = documents.get(uri) else {
+			return Vec::new();
+		};
+		if self.exclusion(document).is_some() {
+			return Vec::new();
+		}
+
+		let edits = document
+			.checker
+			.check()
+			.into_iter()
+			.filter_map(|diag| {
+				let DiagnosticKind::Typo { correction } = diag.kind else {
+					return None;
+				};
+				let range = document.source.span_to_range(diag.span)?;
+				Some(TextEdit::new(range.to_lsp_type(), correction))
+			})
+			.collect();
+		drop(documents);
+		edits
+	}
+
 	#[tracing::instrument(skip_all)]
 	async fn word_at(&self, range: Range) {}
 }
@@ -72,14 +421,29 @@ impl Backend {
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
 	#[tracing::instrument(skip_all)]
-	async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+	async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+		*self.workspace.write() = Workspace::from_params(&params);
+
+		if let Some(options) = params.initialization_options {
+			match Config::from_settings(options) {
+				Ok(config) => *self.config.write() = config,
+				Err(err) => tracing::warn!("invalid initialization options: {err}"),
+			}
+		}
+		self.reload_dictionaries().await;
+
 		Ok(InitializeResult {
 			server_info: Some(ServerInfo {
 				name: "lspelling".into(),
 				version: Some(env!("CARGO_PKG_VERSION").into()),
 			}),
 			capabilities: ServerCapabilities {
-				code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+				code_action_provider: Some(CodeActionProviderCapability::Options(
+					CodeActionOptions {
+						code_action_kinds: Some(vec![CodeActionKind::QUICKFIX, FIX_ALL_KIND]),
+						..Default::default()
+					},
+				)),
 				text_document_sync: Some(TextDocumentSyncCapability::Kind(
 					// TextDocumentSyncKind::INCREMENTAL,
 					TextDocumentSyncKind::FULL,
@@ -94,13 +458,76 @@ impl LanguageServer for Backend {
 	}
 
 	#[tracing::instrument(skip_all)]
-	async fn initialized(&self, _: InitializedParams) {}
+	async fn initialized(&self, _: InitializedParams) {
+		self.reload_project_words().await;
+		self.reload_word_lists().await;
+		self.reload_imported().await;
+		self.register_file_watchers().await;
+	}
 
 	#[tracing::instrument(skip_all)]
 	async fn shutdown(&self) -> Result<()> {
 		Ok(())
 	}
 
+	#[tracing::instrument(skip_all)]
+	async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+		let config = match Config::from_settings(params.settings) {
+			Ok(config) => config,
+			Err(err) => {
+				tracing::warn!("invalid configuration: {err}");
+				return;
+			}
+		};
+
+		let dictionaries_changed = {
+			let mut current = self.config.write();
+			let changed = current.dictionaries != config.dictionaries;
+			*current = config;
+			changed
+		};
+		if dictionaries_changed {
+			self.reload_dictionaries().await;
+			self.reregister_file_watchers().await;
+		}
+
+		self.refresh_documents().await;
+	}
+
+	#[tracing::instrument(skip_all)]
+	async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+		let paths = params
+			.changes
+			.iter()
+			.filter_map(|event| uri_to_path(&event.uri))
+			.collect::<Vec<_>>();
+		let dictionaries = self.config.read().dictionaries.clone();
+
+		let cargo_changed = paths.iter().any(|path| is_cargo_file(path));
+		let word_lists_changed = paths.iter().any(|path| wordlists::is_word_list(path));
+		let imported_changed = paths.iter().any(|path| is_imported_config(path));
+		let dictionaries_changed = paths
+			.iter()
+			.any(|path| dictionary::is_dictionary_file(path, &dictionaries));
+
+		if cargo_changed {
+			self.reload_project_words().await;
+		}
+		if word_lists_changed {
+			self.reload_word_lists().await;
+		}
+		if imported_changed {
+			self.reload_imported().await;
+		}
+		if dictionaries_changed {
+			self.reload_dictionaries().await;
+		}
+
+		if cargo_changed || word_lists_changed || imported_changed || dictionaries_changed {
+			self.refresh_documents().await;
+		}
+	}
+
 	#[tracing::instrument(skip_all, fields(document = %__arg1.text_document.uri.path().segments().last().unwrap_or_default()))]
 	async fn did_open(
 		&self,
@@ -110,13 +537,20 @@ impl LanguageServer for Backend {
 		let uri = text_document.uri.clone();
 
 		let source = Source::new(&text_document.text);
-		let checker = Checker::new(self.dictionary.clone(), &text_document.language_id, &source);
+		// The backend depends on the checker's language, `configure` sets it
+		let mut checker = Checker::new(
+			Box::<Composite>::default(),
+			&text_document.language_id,
+			&source,
+		);
+		self.configure(&mut checker, &uri);
 
 		// its late, im tired
 		#[allow(unsafe_code)]
 		let checker = unsafe { std::mem::transmute::<Checker<'_>, Checker<'_>>(checker) };
 
 		let ck_doc = CheckedDocument {
+			path_ignored: self.is_path_ignored(&text_document.uri),
 			item: text_document,
 			source,
 			checker,
@@ -160,46 +594,218 @@ impl LanguageServer for Backend {
 
 	#[tracing::instrument(skip_all)]
 	async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
-		let Some(diagnostic) = params
-			.context
-			.diagnostics
-			.into_iter()
-			.find(|diag| diag.code == Some(NumberOrString::Number(1)))
-		else {
-			return Ok(None);
+		let edit = |edits: Vec<TextEdit>| {
+			let mut hm = HashMap::new();
+			hm.insert(params.text_document.uri.clone(), edits);
+			WorkspaceEdit::new(hm)
 		};
 
-		let data = diagnostic.data.as_ref().unwrap();
-		let tagged_word = data.to_string();
+		// Applied on save by editors configured to run `source.fixAll`
+		let only = params.context.only.as_deref().unwrap_or_default();
+		if is_requested(&FIX_ALL_KIND, only) {
+			let edits = self.fix_all_edits(&params.text_document.uri).await;
+			if edits.is_empty() {
+				return Ok(None);
+			}
+			return Ok(Some(vec![CodeActionOrCommand::CodeAction(CodeAction {
+				title: "Fix all common misspellings".to_owned(),
+				kind: Some(FIX_ALL_KIND),
+				edit: Some(edit(edits)),
+				..Default::default()
+			})]));
+		}
+
+		// Repeated words, common misspellings and grammar rules have a single fix
+		let fixes = params
+			.context
+			.diagnostics
+			.iter()
+			.filter_map(|diag| {
+				let (title, fix) = match diag.code.as_ref()? {
+					NumberOrString::Number(REPEATED_CODE) => {
+						let removal = serde_json::from_value::<Range>(diag.data.clone()?).ok()?;
+						(
+							"Remove the repeated word".to_owned(),
+							TextEdit::new(removal, String::new()),
+						)
+					}
+					NumberOrString::Number(TYPO_CODE) | NumberOrString::String(_) => {
+						let replacement = diag.data.as_ref()?.as_str()?;
+						(
+							format!("Replace with `{replacement}`"),
+							TextEdit::new(diag.range, replacement.to_owned()),
+						)
+					}
+					NumberOrString::Number(_) => return None,
+				};
+
+				Some(CodeActionOrCommand::CodeAction(CodeAction {
+					title,
+					kind: Some(CodeActionKind::QUICKFIX),
+					diagnostics: Some(vec![diag.clone()]),
+					edit: Some(edit(vec![fix])),
+					is_preferred: Some(true),
+					..Default::default()
+				}))
+			})
+			.collect::<Vec<_>>();
 
-		// let suggest = dict.suggest(tagged_word);
-		let suggest = vec![];
+		let Some(diagnostic) = params.context.diagnostics.into_iter().find(|diag| {
+			matches!(
+				diag.code,
+				Some(NumberOrString::Number(MISSPELLED_CODE | TYPO_CODE))
+			)
+		}) else {
+			return Ok((!fixes.is_empty()).then_some(fixes));
+		};
+		// The data of a typo is its correction, which is already a fix
+		let correction = (diagnostic.code == Some(NumberOrString::Number(TYPO_CODE)))
+			.then(|| diagnostic.data.as_ref().and_then(Value::as_str))
+			.flatten();
 
-		let mut actions = suggest
+		let documents = self.documents.read().await;
+		let Some(document) = documents.get(&params.text_document.uri) else {
+			return Ok(None);
+		};
+		let misspellings = document
+			.checker
+			.check()
 			.into_iter()
-			.map(|replacement_word: String| {
-				let replace_word_edit = TextEdit::new(diagnostic.range, replacement_word.clone());
+			.filter(|diag| {
+				matches!(
+					diag.kind,
+					DiagnosticKind::Misspelled | DiagnosticKind::Typo { .. }
+				)
+			})
+			.filter_map(|diag| {
+				let range = document.source.span_to_range(diag.span)?;
+				Some((range.to_lsp_type(), diag))
+			})
+			.collect::<Vec<_>>();
+		let tagged_word = if correction.is_some() {
+			misspellings
+				.iter()
+				.find(|(range, _)| *range == diagnostic.range)
+				.map(|(_, diag)| diag.word.clone())
+		} else {
+			diagnostic
+				.data
+				.as_ref()
+				.and_then(Value::as_str)
+				.map(ToOwned::to_owned)
+		};
+		let Some(tagged_word) = tagged_word else {
+			return Ok((!fixes.is_empty()).then_some(fixes));
+		};
 
-				CodeActionOrCommand::CodeAction(CodeAction {
-					title: format!("Replace with `{replacement_word}`"),
-					kind: Some(CodeActionKind::QUICKFIX),
-					diagnostics: Some(vec![diagnostic.clone()]),
-					edit: Some(WorkspaceEdit::new({
-						let mut hm = HashMap::new();
-						hm.insert(params.text_document.uri.clone(), vec![replace_word_edit]);
-						hm
-					})),
-					..Default::default()
-				})
+		let suggest = document.checker.suggest(&tagged_word);
+		// Every misspelling of the same word
+		let occurrences = misspellings
+			.into_iter()
+			.filter(|(_, diag)| diag.word.to_lowercase() == tagged_word.to_lowercase())
+			.collect::<Vec<_>>();
+		// Suggestions written like each occurrence, in its case or in the
+		// convention of the identifier it is part of
+		let replacements = suggest
+			.into_iter()
+			.map(|suggestion| {
+				let edits = occurrences
+					.iter()
+					.map(|(range, diag)| {
+						TextEdit::new(*range, diag.replacement(&document.source, &suggestion))
+					})
+					.collect::<Vec<_>>();
+				(suggestion, edits)
 			})
 			.collect::<Vec<_>>();
+		let ignore_edits = [
+			(
+				format!("Ignore `{tagged_word}` in this file"),
+				document.ignore_word_edit(&tagged_word),
+			),
+			(
+				"Ignore spelling on this line".to_owned(),
+				document.ignore_line_edit(diagnostic.range.start.line),
+			),
+		];
+		drop(documents);
+
+		// At the root of a workspace folder, several layers share a list
+		let layers = uri_to_path(&params.text_document.uri).map_or_else(Vec::new, |path| {
+			let workspace = self.workspace.read();
+			let mut lists = HashSet::new();
+			Layer::ALL
+				.into_iter()
+				.filter(|layer| {
+					layer
+						.path(&workspace, &path)
+						.is_some_and(|list| lists.insert(list))
+				})
+				.collect()
+		});
+
+		let mut actions = fixes;
+		actions.extend(
+			replacements
+				.into_iter()
+				.flat_map(|(replacement_word, edits)| {
+					let replace_word_edit = edits
+						.iter()
+						.find(|edit| edit.range == diagnostic.range)
+						.cloned()
+						.unwrap_or_else(|| {
+							TextEdit::new(diagnostic.range, replacement_word.clone())
+						});
+					let mut replacements = Vec::new();
+					if correction != Some(replace_word_edit.new_text.as_str()) {
+						replacements.push(CodeActionOrCommand::CodeAction(CodeAction {
+							title: format!("Replace with `{}`", replace_word_edit.new_text),
+							kind: Some(CodeActionKind::QUICKFIX),
+							diagnostics: Some(vec![diagnostic.clone()]),
+							edit: Some(edit(vec![replace_word_edit])),
+							..Default::default()
+						}));
+					}
+
+					if edits.len() > 1 {
+						let title = format!(
+							"Replace all {} occurrences of `{tagged_word}` with `{replacement_word}` in this file",
+							edits.len()
+						);
+						replacements.push(CodeActionOrCommand::CodeAction(CodeAction {
+							title,
+							kind: Some(CodeActionKind::QUICKFIX),
+							diagnostics: Some(vec![diagnostic.clone()]),
+							edit: Some(edit(edits)),
+							..Default::default()
+						}));
+					}
+					replacements
+				}),
+		);
 
-		actions.push(CodeActionOrCommand::CodeAction(CodeAction {
-			title: "Add this word to the dictionary".into(),
-			kind: Some(CodeActionKind::QUICKFIX),
-			diagnostics: Some(vec![diagnostic.clone()]),
-			command: Some(AddToDict::command(tagged_word)),
-			..Default::default()
+		actions.extend(layers.into_iter().map(|layer| {
+			CodeActionOrCommand::CodeAction(CodeAction {
+				title: format!("Add `{tagged_word}` to the {layer} word list"),
+				kind: Some(CodeActionKind::QUICKFIX),
+				diagnostics: Some(vec![diagnostic.clone()]),
+				command: Some(AddToDict::command(
+					tagged_word.clone(),
+					layer,
+					&params.text_document.uri,
+				)),
+				..Default::default()
+			})
+		}));
+
+		actions.extend(ignore_edits.into_iter().map(|(title, ignore_edit)| {
+			CodeActionOrCommand::CodeAction(CodeAction {
+				title,
+				kind: Some(CodeActionKind::QUICKFIX),
+				diagnostics: Some(vec![diagnostic.clone()]),
+				edit: Some(edit(vec![ignore_edit])),
+				..Default::default()
+			})
 		}));
 
 		Ok(Some(actions))
@@ -208,24 +814,70 @@ impl LanguageServer for Backend {
 	async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
 		match params.command.as_str() {
 			ADD_TO_DICT => {
-				// TODO: logic to add custom word to dict
-				let word = params.arguments[0].as_str().unwrap();
-				tracing::error!("adding word {} to dictionary is not implemented", word);
+				let (word, layer, uri) = match AddToDict::arguments(params.arguments) {
+					Ok(arguments) => arguments,
+					Err(err) => {
+						tracing::warn!("invalid `{ADD_TO_DICT}` arguments: {err}");
+						return Ok(None);
+					}
+				};
+
+				let path = uri_to_path(&uri)
+					.and_then(|document| layer.path(&self.workspace.read(), &document));
+				let Some(path) = path else {
+					self.client
+						.show_message(
+							MessageType::WARNING,
+							format!("no {layer} word list applies to this document"),
+						)
+						.await;
+					return Ok(None);
+				};
+
+				if let Err(err) = wordlists::append(&path, &word) {
+					self.client
+						.show_message(
+							MessageType::ERROR,
+							format!("could not add `{word}` to `{}`: {err}", path.display()),
+						)
+						.await;
+					return Ok(None);
+				}
 
 				self.client
 					.log_message(
 						MessageType::INFO,
-						format!("`{word}` was indeed added to dictionary"),
+						format!("added `{word}` to `{}`", path.display()),
 					)
 					.await;
+				self.reload_word_lists().await;
+				self.refresh_documents().await;
 			}
-			_ => todo!(),
+			_ => return Err(jsonrpc::Error::method_not_found()),
 		};
 
 		Ok(None)
 	}
 }
 
+/// Runs `f`, which reads or walks the filesystem, outside of the async runtime
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+	tokio::task::spawn_blocking(f)
+		.await
+		.inspect_err(|err| tracing::error!("blocking task failed: {err}"))
+		.ok()
+}
+
+/// Whether `kind` is among or beneath one of the `only` kinds, e.g.
+/// `source.fixAll.lspelling` is beneath `source.fixAll`
+fn is_requested(kind: &CodeActionKind, only: &[CodeActionKind]) -> bool {
+	only.iter().any(|requested| {
+		kind.as_str()
+			.strip_prefix(requested.as_str())
+			.is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+	})
+}
+
 fn tracing_panic_hook(panic_info: &PanicInfo) {
 	let payload = panic_info
 		.payload()
@@ -0,0 +1,140 @@
+use lspelling_wordc::wordlist::WordList;
+use serde::{Deserialize, Serialize};
+use std::{
+	env, fmt,
+	fs::{self, OpenOptions},
+	io::{self, Write as _},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use crate::workspace::{read, Workspace};
+
+/// File name of the workspace and per-directory word lists
+pub(crate) const WORDS_FILE: &str = ".lspelling-words";
+
+/// Where a word list applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Layer {
+	/// Personal list, shared by every workspace
+	User,
+	/// List at the root of a workspace folder
+	Workspace,
+	/// List in the directory of a document, applying to files beneath it
+	Directory,
+}
+
+impl Layer {
+	pub(crate) const ALL: [Self; 3] = [Self::User, Self::Workspace, Self::Directory];
+
+	/// File words are added to for a document at `document`
+	pub(crate) fn path(self, workspace: &Workspace, document: &Path) -> Option<PathBuf> {
+		match self {
+			Self::User => user_path(),
+			Self::Workspace => workspace
+				.root_of(document)
+				.map(|root| root.join(WORDS_FILE)),
+			// Lists are only looked for in the workspace
+			Self::Directory => {
+				workspace.root_of(document)?;
+				document.parent().map(|dir| dir.join(WORDS_FILE))
+			}
+		}
+	}
+}
+
+impl fmt::Display for Layer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::User => "user",
+			Self::Workspace => "workspace",
+			Self::Directory => "directory",
+		})
+	}
+}
+
+/// Word lists of every layer
+#[derive(Debug, Default)]
+pub(crate) struct WordLists {
+	user: Arc<WordList>,
+	/// Workspace and per-directory lists, by directory they apply to
+	directories: Vec<(PathBuf, Arc<WordList>)>,
+}
+
+impl WordLists {
+	pub(crate) fn load(workspace: &Workspace) -> Self {
+		let user = user_path()
+			.map(|path| read_words(&path))
+			.unwrap_or_default();
+		let directories = workspace
+			.files_named(WORDS_FILE)
+			.into_iter()
+			.filter_map(|path| {
+				let words = read_words(&path);
+				Some((path.parent()?.to_owned(), Arc::new(words)))
+			})
+			.collect();
+
+		Self {
+			user: Arc::new(user),
+			directories,
+		}
+	}
+
+	/// Lists applying to a document at `path`, the user one first
+	pub(crate) fn for_path(&self, path: Option<&Path>) -> Vec<Arc<WordList>> {
+		let mut lists = vec![Arc::clone(&self.user)];
+		lists.extend(
+			self.directories
+				.iter()
+				.filter(|(dir, _)| path.is_some_and(|path| path.starts_with(dir)))
+				.map(|(_, words)| Arc::clone(words)),
+		);
+		lists
+	}
+
+	/// Number of words in every list
+	pub(crate) fn len(&self) -> usize {
+		self.directories
+			.iter()
+			.map(|(_, words)| words.len())
+			.sum::<usize>()
+			+ self.user.len()
+	}
+}
+
+/// Personal list, under `$XDG_CONFIG_HOME/lspelling/words.txt`
+pub(crate) fn user_path() -> Option<PathBuf> {
+	env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.filter(|dir| dir.is_absolute())
+		.or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+		.map(|config| config.join("lspelling").join("words.txt"))
+}
+
+/// Whether `path` is a word list of any layer
+pub(crate) fn is_word_list(path: &Path) -> bool {
+	path.file_name() == Some(WORDS_FILE.as_ref()) || user_path().is_some_and(|user| user == path)
+}
+
+/// Appends `word` to the list at `path`, creating it if needed
+pub(crate) fn append(path: &Path, word: &str) -> io::Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+
+	let content = fs::read_to_string(path).unwrap_or_default();
+	let separator = if content.is_empty() || content.ends_with('\n') {
+		""
+	} else {
+		"\n"
+	};
+
+	let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+	writeln!(file, "{separator}{word}")
+}
+
+fn read_words(path: &Path) -> WordList {
+	read(path).map_or_else(WordList::default, |content| WordList::parse(&content))
+}
@@ -0,0 +1,183 @@
+use ignore::WalkBuilder;
+use lspelling_wordc::{vocabulary::cargo, wordlist::WordList};
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
+use tower_lsp::lsp_types::{InitializeParams, Uri};
+
+const CARGO_FILES: [&str; 2] = ["Cargo.toml", "Cargo.lock"];
+
+/// Directories that are never walked when looking for project files
+const SKIPPED_DIRS: [&str; 2] = ["target", "node_modules"];
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Workspace {
+	roots: Vec<PathBuf>,
+}
+
+impl Workspace {
+	pub(crate) fn from_params(params: &InitializeParams) -> Self {
+		#[allow(deprecated)]
+		let root_uri = params.root_uri.as_ref();
+
+		let roots = params.workspace_folders.as_ref().map_or_else(
+			|| root_uri.and_then(uri_to_path).into_iter().collect(),
+			|folders| {
+				folders
+					.iter()
+					.filter_map(|folder| uri_to_path(&folder.uri))
+					.collect()
+			},
+		);
+
+		Self { roots }
+	}
+
+	pub(crate) fn roots(&self) -> &[PathBuf] {
+		&self.roots
+	}
+
+	/// Workspace folder containing `path`
+	pub(crate) fn root_of(&self, path: &Path) -> Option<&Path> {
+		self.roots
+			.iter()
+			.find(|root| path.starts_with(root))
+			.map(PathBuf::as_path)
+	}
+
+	/// Every file of the workspace named `name`
+	pub(crate) fn files_named(&self, name: &str) -> Vec<PathBuf> {
+		self.roots
+			.iter()
+			.flat_map(|root| find_files(root, &[name]))
+			.collect()
+	}
+
+	/// Crate names found in every Cargo manifest and lockfile of the workspace,
+	/// along with the names manifests' crates are referred to in code
+	pub(crate) fn cargo_vocabulary(&self) -> (WordList, Vec<String>) {
+		let mut words = WordList::default();
+		let mut crates = Vec::new();
+
+		for path in self
+			.roots
+			.iter()
+			.flat_map(|root| find_files(root, &CARGO_FILES))
+		{
+			let content = match fs::read_to_string(&path) {
+				Ok(content) => content,
+				Err(err) => {
+					tracing::warn!("could not read `{}`: {err}", path.display());
+					continue;
+				}
+			};
+
+			let parsed = if path.ends_with("Cargo.lock") {
+				cargo::lockfile_words(&content)
+			} else {
+				cargo::manifest_crates(&content)
+					.map(|names| crates.extend(names))
+					.and_then(|()| cargo::manifest_words(&content))
+			};
+			match parsed {
+				Ok(names) => words.extend(names),
+				Err(err) => tracing::warn!("could not parse `{}`: {err}", path.display()),
+			}
+		}
+
+		crates.sort_unstable();
+		crates.dedup();
+		(words, crates)
+	}
+}
+
+pub(crate) fn is_cargo_file(path: &Path) -> bool {
+	path.file_name()
+		.and_then(|name| name.to_str())
+		.is_some_and(|name| CARGO_FILES.contains(&name))
+}
+
+/// Converts a `file://` uri to a local path
+pub(crate) fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+	if !uri.scheme()?.eq_lowercase("file") {
+		return None;
+	}
+
+	let path = uri.path().as_estr().decode().into_string_lossy();
+	Some(PathBuf::from(path.as_ref()))
+}
+
+/// Content of the file at `path`, `None` if it is missing or could not be read,
+/// which is logged
+pub(crate) fn read(path: &Path) -> Option<String> {
+	match fs::read_to_string(path) {
+		Ok(content) => Some(content),
+		Err(err) => {
+			if err.kind() != io::ErrorKind::NotFound {
+				tracing::warn!("could not read `{}`: {err}", path.display());
+			}
+			None
+		}
+	}
+}
+
+/// Recursively collects files named after one of `names`, skipping hidden and
+/// build directories, but not hidden files, e.g. `.lspelling-words`
+///
+/// Symbolic links are not followed, so that link cycles are not walked forever.
+/// This blocks on filesystem access.
+fn find_files(dir: &Path, names: &[&str]) -> Vec<PathBuf> {
+	WalkBuilder::new(dir)
+		.standard_filters(false)
+		.follow_links(false)
+		.filter_entry(|entry| {
+			let is_dir = entry.file_type().is_some_and(|kind| kind.is_dir());
+			// The walked directory itself may be hidden
+			!(is_dir
+				&& entry.depth() > 0
+				&& entry
+					.file_name()
+					.to_str()
+					.is_some_and(|name| name.starts_with('.') || SKIPPED_DIRS.contains(&name)))
+		})
+		.build()
+		.filter_map(|entry| {
+			entry
+				.inspect_err(|err| tracing::debug!("could not walk workspace: {err}"))
+				.ok()
+		})
+		.filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+		.filter(|entry| {
+			entry
+				.file_name()
+				.to_str()
+				.is_some_and(|name| names.contains(&name))
+		})
+		.map(ignore::DirEntry::into_path)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::wordlists::WORDS_FILE;
+
+	#[test]
+	fn find_hidden_files_outside_hidden_dirs() {
+		let root = std::env::temp_dir().join("lspelling-find-files");
+		let _ = fs::remove_dir_all(&root);
+		for dir in ["src/nested", ".git", "target"] {
+			fs::create_dir_all(root.join(dir)).unwrap();
+			fs::write(root.join(dir).join(WORDS_FILE), "word\n").unwrap();
+		}
+
+		let workspace = Workspace {
+			roots: vec![root.clone()],
+		};
+		assert_eq!(
+			workspace.files_named(WORDS_FILE),
+			[root.join("src/nested").join(WORDS_FILE)]
+		);
+	}
+}
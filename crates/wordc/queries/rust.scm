@@ -5,6 +5,32 @@
   (type_identifier)
 ] @ident
 
+; Identifiers where they are declared, used when only checking definitions
+(let_declaration pattern: (identifier) @ident.definition)
+(let_declaration pattern: (mut_pattern (identifier) @ident.definition))
+(let_declaration pattern: (tuple_pattern (identifier) @ident.definition))
+(let_condition pattern: (identifier) @ident.definition)
+(tuple_struct_pattern type: (_) (identifier) @ident.definition)
+(for_expression pattern: (identifier) @ident.definition)
+(parameter pattern: (identifier) @ident.definition)
+(parameter pattern: (mut_pattern (identifier) @ident.definition))
+(closure_parameters (identifier) @ident.definition)
+
+(function_item name: (identifier) @ident.definition)
+(function_signature_item name: (identifier) @ident.definition)
+(macro_definition name: (identifier) @ident.definition)
+(const_item name: (identifier) @ident.definition)
+(static_item name: (identifier) @ident.definition)
+(mod_item name: (identifier) @ident.definition)
+
+(struct_item name: (type_identifier) @ident.definition)
+(enum_item name: (type_identifier) @ident.definition)
+(union_item name: (type_identifier) @ident.definition)
+(trait_item name: (type_identifier) @ident.definition)
+(type_item name: (type_identifier) @ident.definition)
+(enum_variant name: (identifier) @ident.definition)
+(field_declaration name: (field_identifier) @ident.definition)
+
 
 ; String literals' content to process as a sentence
 (string_content) @sentence.string
@@ -0,0 +1,363 @@
+//! Sources of correctly spelled words consulted by the [`Checker`](crate::checker::Checker)
+
+use ruspell::Dictionary;
+use std::{fmt, sync::Arc};
+
+use crate::{suggest::MAX_SUGGESTIONS, wordlist::WordList};
+
+pub trait SpellBackend: Send + Sync + fmt::Debug {
+	/// Whether `word` is correctly spelled
+	fn lookup(&self, word: &str) -> bool;
+
+	/// Like [`lookup`](Self::lookup), ignoring case, e.g. accepts `github` if
+	/// `GitHub` is known
+	fn lookup_folded(&self, word: &str) -> bool {
+		case_variants(word).any(|variant| self.lookup(&variant))
+	}
+
+	/// Correctly spelled candidates to replace `word` with, best first
+	fn suggest(&self, word: &str) -> Vec<String>;
+
+	/// Accepts `word` from now on
+	fn add_word(&mut self, word: &str);
+
+	/// Names of the dictionaries consulted by this backend
+	fn dictionaries(&self) -> Vec<String> {
+		Vec::new()
+	}
+
+	/// Language of the words, `None` for lists that are not tied to one (e.g. jargon)
+	fn language(&self) -> Option<&str> {
+		None
+	}
+
+	/// Whether any consulted dictionary is of a language, unlike e.g. `custom.dic`
+	fn has_language(&self) -> bool {
+		self.language().is_some_and(is_language_tag)
+	}
+
+	/// Whether a dictionary of `language` is consulted by this backend
+	fn supports(&self, language: &str) -> bool {
+		self.language()
+			.is_some_and(|own| language_matches(own, language))
+	}
+
+	/// Like [`lookup`](Self::lookup), but only consulting dictionaries of
+	/// `language` and lists that are not tied to a language
+	fn lookup_in(&self, word: &str, language: &str) -> bool {
+		match self.language() {
+			Some(own) if !language_matches(own, language) => false,
+			_ => self.lookup(word),
+		}
+	}
+
+	/// Both [`lookup_folded`](Self::lookup_folded) and [`lookup_in`](Self::lookup_in)
+	fn lookup_folded_in(&self, word: &str, language: &str) -> bool {
+		match self.language() {
+			Some(own) if !language_matches(own, language) => false,
+			_ => self.lookup_folded(word),
+		}
+	}
+}
+
+/// `word` as written, lowercased, capitalized and uppercased
+fn case_variants(word: &str) -> impl Iterator<Item = String> {
+	let lowercase = word.to_lowercase();
+	let mut chars = lowercase.chars();
+	let capitalized = chars
+		.next()
+		.map(|first| first.to_uppercase().chain(chars).collect::<String>())
+		.unwrap_or_default();
+
+	[word.to_owned(), word.to_uppercase(), capitalized, lowercase].into_iter()
+}
+
+/// Whether `name` looks like a language tag, e.g. `fr` or `en_US` but not `custom`
+fn is_language_tag(name: &str) -> bool {
+	let (language, region) = name.split_once(['_', '-']).unwrap_or((name, "a"));
+	(2..=3).contains(&language.len())
+		&& language.chars().all(|c| c.is_ascii_lowercase())
+		&& !region.is_empty()
+		&& region.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Whether two language tags designate the same language, e.g. `fr` and `fr_FR`
+/// match but `en_US` and `en_GB` do not
+#[must_use]
+pub fn language_matches(a: &str, b: &str) -> bool {
+	let normalize = |tag: &str| tag.replace('-', "_").to_lowercase();
+	let (a, b) = (normalize(a), normalize(b));
+	let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+	long.strip_prefix(&short)
+		.is_some_and(|rest| rest.is_empty() || rest.starts_with('_'))
+}
+
+/// A hunspell dictionary
+///
+/// Dictionaries do not suggest their own words, suggestions come from word
+/// lists and words added at runtime.
+#[derive(Debug, Clone)]
+pub struct Hunspell {
+	/// Usually the language tag, e.g. `en_US`
+	name: String,
+	dictionary: Dictionary,
+	/// Words added at runtime
+	added: WordList,
+	/// Entries with inner capitals, which are also accepted in any case
+	mixed_case: WordList,
+}
+
+impl Hunspell {
+	#[must_use]
+	pub fn new(name: impl Into<String>, dictionary: Dictionary) -> Self {
+		Self {
+			name: name.into(),
+			dictionary,
+			added: WordList::default(),
+			mixed_case: WordList::default(),
+		}
+	}
+
+	/// Accepts entries of the `.dic` file content `dic` with inner capitals in
+	/// any case, e.g. `github` for `GitHub`, which case variants of the word do
+	/// not reach
+	#[must_use]
+	pub fn with_mixed_case(mut self, dic: &str) -> Self {
+		self.mixed_case = dic
+			.lines()
+			// The first line is the number of entries
+			.skip(1)
+			.filter_map(|line| line.split(['/', '\t', ' ']).next())
+			.filter(|word| word.chars().skip(1).any(char::is_uppercase))
+			.map(ToOwned::to_owned)
+			.collect();
+		self
+	}
+}
+
+impl SpellBackend for Hunspell {
+	fn lookup(&self, word: &str) -> bool {
+		self.added.contains(word)
+			|| self
+				.dictionary
+				.lookup(word)
+				.inspect_err(|err| tracing::warn!("hunspell lookup of `{word}` failed: {err:?}"))
+				.unwrap_or(false)
+	}
+
+	/// Only words added at runtime are suggested, as ruspell has no suggestion
+	/// engine yet
+	fn suggest(&self, word: &str) -> Vec<String> {
+		self.added.suggest(word)
+	}
+
+	fn lookup_folded(&self, word: &str) -> bool {
+		case_variants(word).any(|variant| self.lookup(&variant))
+			|| self.mixed_case.contains_folded(word)
+	}
+
+	fn add_word(&mut self, word: &str) {
+		self.added.insert(word);
+	}
+
+	fn dictionaries(&self) -> Vec<String> {
+		vec![self.name.clone()]
+	}
+
+	fn language(&self) -> Option<&str> {
+		Some(&self.name)
+	}
+}
+
+impl SpellBackend for WordList {
+	fn lookup(&self, word: &str) -> bool {
+		self.contains(word)
+	}
+
+	fn lookup_folded(&self, word: &str) -> bool {
+		self.contains_folded(word)
+	}
+
+	fn suggest(&self, word: &str) -> Vec<String> {
+		Self::suggest(self, word)
+	}
+
+	fn add_word(&mut self, word: &str) {
+		self.insert(word);
+	}
+}
+
+/// Shared backends are copied on write
+impl<B: SpellBackend + Clone> SpellBackend for Arc<B> {
+	fn lookup(&self, word: &str) -> bool {
+		B::lookup(self, word)
+	}
+
+	fn suggest(&self, word: &str) -> Vec<String> {
+		B::suggest(self, word)
+	}
+
+	fn add_word(&mut self, word: &str) {
+		Self::make_mut(self).add_word(word);
+	}
+
+	fn dictionaries(&self) -> Vec<String> {
+		B::dictionaries(self)
+	}
+
+	fn language(&self) -> Option<&str> {
+		B::language(self)
+	}
+
+	fn has_language(&self) -> bool {
+		B::has_language(self)
+	}
+
+	fn supports(&self, language: &str) -> bool {
+		B::supports(self, language)
+	}
+
+	fn lookup_in(&self, word: &str, language: &str) -> bool {
+		B::lookup_in(self, word, language)
+	}
+
+	fn lookup_folded(&self, word: &str) -> bool {
+		B::lookup_folded(self, word)
+	}
+
+	fn lookup_folded_in(&self, word: &str, language: &str) -> bool {
+		B::lookup_folded_in(self, word, language)
+	}
+}
+
+/// Union of several backends, a word is accepted if any of them knows it
+#[derive(Debug, Default)]
+pub struct Composite {
+	backends: Vec<Box<dyn SpellBackend>>,
+}
+
+impl Composite {
+	/// Backends are consulted in order, added words go to the first one
+	#[must_use]
+	pub fn new(backends: Vec<Box<dyn SpellBackend>>) -> Self {
+		Self { backends }
+	}
+
+	pub fn push(&mut self, backend: impl SpellBackend + 'static) {
+		self.backends.push(Box::new(backend));
+	}
+}
+
+impl SpellBackend for Composite {
+	fn lookup(&self, word: &str) -> bool {
+		self.backends.iter().any(|backend| backend.lookup(word))
+	}
+
+	fn suggest(&self, word: &str) -> Vec<String> {
+		let mut suggestions = Vec::new();
+		for suggestion in self
+			.backends
+			.iter()
+			.flat_map(|backend| backend.suggest(word))
+		{
+			if !suggestions.contains(&suggestion) {
+				suggestions.push(suggestion);
+			}
+		}
+		suggestions.truncate(MAX_SUGGESTIONS);
+		suggestions
+	}
+
+	fn add_word(&mut self, word: &str) {
+		if let Some(backend) = self.backends.first_mut() {
+			backend.add_word(word);
+		}
+	}
+
+	fn dictionaries(&self) -> Vec<String> {
+		self.backends
+			.iter()
+			.flat_map(|backend| backend.dictionaries())
+			.collect()
+	}
+
+	fn has_language(&self) -> bool {
+		self.backends.iter().any(|backend| backend.has_language())
+	}
+
+	fn supports(&self, language: &str) -> bool {
+		self.backends
+			.iter()
+			.any(|backend| backend.supports(language))
+	}
+
+	fn lookup_in(&self, word: &str, language: &str) -> bool {
+		self.backends
+			.iter()
+			.any(|backend| backend.lookup_in(word, language))
+	}
+
+	fn lookup_folded(&self, word: &str) -> bool {
+		self.backends
+			.iter()
+			.any(|backend| backend.lookup_folded(word))
+	}
+
+	fn lookup_folded_in(&self, word: &str, language: &str) -> bool {
+		self.backends
+			.iter()
+			.any(|backend| backend.lookup_folded_in(word, language))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn composite_lookup_and_suggest() {
+		let mut composite = Composite::default();
+		composite.push(WordList::parse("word\nworld"));
+		composite.push(Arc::new(WordList::parse("sword\nword")));
+
+		assert!(composite.lookup("sword"));
+		assert!(!composite.lookup("wrod"));
+		assert_eq!(composite.suggest("wrod"), ["word", "world", "sword"]);
+
+		composite.add_word("wrod");
+		assert!(composite.lookup("wrod"));
+
+		composite.push(WordList::parse("GitHub"));
+		assert!(!composite.lookup("github"));
+		assert!(composite.lookup_folded("github"));
+	}
+
+	#[test]
+	fn hunspell_mixed_case_entries() {
+		let dir = std::env::temp_dir().join("lspelling-hunspell-mixed-case");
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("en_US.aff"), "SET UTF-8\n").unwrap();
+		let dic = "2\nGitHub\nword/S\n";
+		std::fs::write(dir.join("en_US.dic"), dic).unwrap();
+
+		let dictionary = Dictionary::from_pair(&dir.join("en_US")).unwrap();
+		let hunspell = Hunspell::new("en_US", dictionary).with_mixed_case(dic);
+
+		assert!(hunspell.lookup("GitHub"));
+		assert!(!hunspell.lookup("github"));
+		assert!(hunspell.lookup_folded("github"));
+		assert!(hunspell.lookup_folded("GITHUB"));
+		assert!(hunspell.lookup_folded("Word"));
+		assert!(!hunspell.lookup_folded("wrod"));
+	}
+
+	#[test]
+	fn match_language_tags() {
+		assert!(language_matches("fr_FR", "fr"));
+		assert!(language_matches("de", "de-DE"));
+		assert!(language_matches("en_US", "en_us"));
+		assert!(!language_matches("en_US", "en_GB"));
+		assert!(!language_matches("fr", "fy"));
+	}
+}
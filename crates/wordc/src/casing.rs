@@ -0,0 +1,147 @@
+//! Casing of replacements, so that fixes keep the style of the text they replace
+
+/// Naming convention of an identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentCase {
+	/// `byeJelloBoulo`
+	Camel,
+	/// `ByeJelloBoulo`
+	Pascal,
+	/// `bye_jello_boulo`
+	Snake,
+	/// `BYE_JELLO_BOULO`
+	ScreamingSnake,
+	/// `bye-jello-boulo`
+	Kebab,
+}
+
+impl IdentCase {
+	/// Convention `ident` is written in, a single lowercase word being snake
+	/// case and a single uppercase one, e.g. `HTTP`, screaming snake case
+	#[must_use]
+	pub fn of(ident: &str) -> Self {
+		let ident = ident.trim_matches(|c: char| !c.is_alphanumeric());
+		let has_lowercase = ident.chars().any(char::is_lowercase);
+
+		if ident.contains('_') || !has_lowercase {
+			if has_lowercase {
+				Self::Snake
+			} else {
+				Self::ScreamingSnake
+			}
+		} else if ident.contains('-') {
+			Self::Kebab
+		} else if ident.starts_with(char::is_uppercase) {
+			Self::Pascal
+		} else if ident.chars().any(char::is_uppercase) {
+			Self::Camel
+		} else {
+			Self::Snake
+		}
+	}
+
+	/// `words` written in this convention, `first` if they start the identifier
+	#[must_use]
+	pub fn join(self, words: &[&str], first: bool) -> String {
+		match self {
+			Self::Snake => words.join("_").to_lowercase(),
+			Self::ScreamingSnake => words.join("_").to_uppercase(),
+			Self::Kebab => words.join("-").to_lowercase(),
+			Self::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+			Self::Camel => words
+				.iter()
+				.enumerate()
+				.map(|(index, word)| {
+					if first && index == 0 {
+						word.to_lowercase()
+					} else {
+						capitalize(word)
+					}
+				})
+				.collect(),
+		}
+	}
+}
+
+/// `replacement` of the word at `offset` in `ident`, in the convention of
+/// the identifier, e.g. `Jelly` for the `Jelo` of `ByeJeloBoulo`
+///
+/// Each word of a suggestion becomes a word of the identifier, so `jell o`
+/// replaces the `Jelo` of `byeJeloBoulo` with `JellO` and the `jelo` of
+/// `bye_jelo` with `jell_o`.
+#[must_use]
+pub fn ident_part(ident: &str, offset: usize, replacement: &str) -> String {
+	let first = !ident[..offset].contains(char::is_alphanumeric);
+	// Suggestions may be several words, e.g. `jell o`, or contractions
+	let words = replacement
+		.split(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '\'' | '’'))
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<_>>();
+
+	IdentCase::of(ident).join(&words, first)
+}
+
+/// `replacement` in the case of `original`: uppercase if it is an uppercase
+/// word, capitalized if it is capitalized and as is otherwise
+#[must_use]
+pub fn match_case(original: &str, replacement: &str) -> String {
+	let mut letters = original.chars().filter(|c| c.is_alphabetic()).peekable();
+	let capitalized = letters.peek().is_some_and(|c| c.is_uppercase());
+	let uppercase = capitalized && letters.clone().count() > 1 && letters.all(char::is_uppercase);
+
+	if uppercase {
+		replacement.to_uppercase()
+	} else if capitalized {
+		capitalize(replacement)
+	} else {
+		replacement.to_owned()
+	}
+}
+
+/// `word` with its first letter in uppercase
+#[must_use]
+pub fn capitalize(word: &str) -> String {
+	let mut chars = word.chars();
+	chars
+		.next()
+		.map(|first| first.to_uppercase().chain(chars).collect())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn match_original_case() {
+		assert_eq!(match_case("wrod", "word"), "word");
+		assert_eq!(match_case("Wrod", "word"), "Word");
+		assert_eq!(match_case("WROD", "word"), "WORD");
+		assert_eq!(match_case("A", "an"), "An");
+		assert_eq!(match_case("github", "GitHub"), "GitHub");
+	}
+
+	#[test]
+	fn ident_conventions() {
+		assert_eq!(IdentCase::of("byeJelo"), IdentCase::Camel);
+		assert_eq!(IdentCase::of("ByeJelo"), IdentCase::Pascal);
+		assert_eq!(IdentCase::of("bye_jelo"), IdentCase::Snake);
+		assert_eq!(IdentCase::of("jelo"), IdentCase::Snake);
+		assert_eq!(IdentCase::of("BYE_JELO"), IdentCase::ScreamingSnake);
+		assert_eq!(IdentCase::of("HTTP"), IdentCase::ScreamingSnake);
+		assert_eq!(IdentCase::of("bye-jelo"), IdentCase::Kebab);
+	}
+
+	#[test]
+	fn replace_ident_parts() {
+		assert_eq!(ident_part("bye_jello_boulo", 4, "jelly"), "jelly");
+		assert_eq!(ident_part("ByeJeloBoulo", 3, "jelly"), "Jelly");
+		// Words of a suggestion are joined like the words of the identifier
+		assert_eq!(ident_part("byeJeloBoulo", 3, "jell o"), "JellO");
+		assert_eq!(ident_part("jeloBoulo", 0, "Jelly"), "jelly");
+		assert_eq!(ident_part("BYE_JELO", 4, "jell-o"), "JELL_O");
+		assert_eq!(ident_part("bye-jelo", 4, "jell o"), "jell-o");
+		assert_eq!(ident_part("_jelo", 1, "don't"), "don_t");
+		assert_eq!(ident_part("GithbUrl", 0, "GitHub"), "GitHub");
+	}
+}
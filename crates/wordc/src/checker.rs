@@ -1,46 +1,172 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Mutex,
+};
 
 use crate::{
-	processor::{FragmentProcessor, TokenKind},
+	backend::SpellBackend,
+	casing,
+	filter::TokenFilters,
+	fragmentizer::{FragmentKind, IdentifierMode},
+	grammar::{self, Rule},
+	normalize::Normalization,
+	processor::{FragmentProcessor, TokenKind, TokenizedFragment},
+	repeated,
 	span::{Source, Span},
+	suggest::MAX_SUGGESTIONS,
+	vocabulary::misspellings,
 };
-use ruspell::Dictionary;
+use regex::Regex;
+use serde::Deserialize;
 
 #[derive(Debug)]
 pub struct WordDiagnostic {
 	pub word: String,
 	pub span: Span,
+	pub kind: DiagnosticKind,
+	/// Identifier the word is part of, if it was found in code
+	pub ident: Option<Span>,
+}
+
+impl WordDiagnostic {
+	/// `suggestion` written like the reported word, or in the convention of
+	/// the identifier it is part of
+	#[must_use]
+	pub fn replacement(&self, source: &Source, suggestion: &str) -> String {
+		self.ident.map_or_else(
+			|| casing::match_case(&self.word, suggestion),
+			|ident| {
+				casing::ident_part(
+					&source.str_from(ident).to_string(),
+					(self.span.low - ident.low).to_usize(),
+					suggestion,
+				)
+			},
+		)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+	/// Not found in any dictionary
+	Misspelled,
+	/// Not found in any dictionary, in a comment, and in the bundled list of
+	/// common misspellings, which have a single possible correction that is
+	/// safe to apply automatically
+	Typo { correction: String },
+	/// Same as the word before it, removing `removal` keeps a single one
+	Repeated { removal: Span },
+	/// Reported by the grammar rule identified by `code`
+	Grammar {
+		code: &'static str,
+		message: String,
+		replacement: Option<String>,
+	},
+}
+
+/// Tunes what gets checked in a source
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Options {
+	pub identifiers: IdentifierMode,
+	/// Skip names imported from other crates, as their spelling is not ours to fix
+	pub ignore_imports: bool,
+	/// Crates the source depends on, e.g. from its Cargo manifest, whose paths
+	/// are skipped along with imports
+	#[serde(skip)]
+	pub crates: Vec<String>,
+	/// Check prose in the language it is detected to be written in, off by
+	/// default; prose in a language without a dictionary is skipped, unless no
+	/// dictionary is of a language (e.g. only `custom.dic`)
+	pub detect_language: bool,
+	/// Patterns of text that is not checked, e.g. ticket ids like `PROJ-\d+`
+	pub ignore_regexes: Vec<String>,
+	pub filters: TokenFilters,
+	/// Unicode form tokens are looked up in, reported words keep their original text
+	pub normalization: Normalization,
+	/// Codes of the grammar rules that are not run, e.g. `a-an`
+	pub disabled_rules: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct Checker<'a> {
 	pub(crate) source: &'a Source,
 	// TODO: dedup with lsp, wa for no send bound
-	dictionary: Dictionary,
+	backend: Box<dyn SpellBackend>,
+	options: Options,
+	/// Compiled [`Options::ignore_regexes`]
+	ignore_regexes: Vec<Regex>,
 	processor: FragmentProcessor<'a>,
+	/// Grammar rules run over prose
+	rules: Vec<Box<dyn Rule>>,
 
 	// TODO: put the mutex higher?
-	cache: Mutex<HashMap<String, bool>>,
+	cache: Mutex<HashMap<CacheKey, HashMap<String, bool>>>,
+	/// Languages selected in the source without a loaded dictionary, warned
+	/// about once
+	unsupported: Mutex<HashSet<String>>,
 }
 
+/// Lookups are cached by language selected in the source and whether case is
+/// ignored, then by word
+type CacheKey = (Option<String>, bool);
+
 /// Initialization
 impl<'a> Checker<'a> {
 	#[must_use]
-	pub fn new(dictionary: Dictionary, language: &str, source: &'a Source) -> Self {
+	pub fn new(backend: Box<dyn SpellBackend>, language: &str, source: &'a Source) -> Self {
+		let options = Options::default();
 		Self {
 			source,
-			dictionary,
-			processor: FragmentProcessor::from_lang(language, source),
+			backend,
+			processor: FragmentProcessor::from_lang(language, source, &options),
+			options,
+			ignore_regexes: Vec::new(),
+			rules: grammar::builtin(),
 
 			cache: Mutex::default(),
+			unsupported: Mutex::default(),
 		}
 	}
 
 	// TODO: remove this from api
 	pub fn replace_src(&mut self, source: &'a Source) {
 		self.source = source;
-		self.processor =
-			FragmentProcessor::from_lang(self.processor.fragmentizer.lang_code(), source);
+		self.processor = FragmentProcessor::from_lang(self.language(), source, &self.options);
+	}
+
+	/// Replaces the options and invalidates previous lookups
+	pub fn set_options(&mut self, options: Options) {
+		self.processor = FragmentProcessor::from_lang(self.language(), self.source, &options);
+		self.ignore_regexes = options
+			.ignore_regexes
+			.iter()
+			.filter_map(|pattern| {
+				Regex::new(pattern)
+					.inspect_err(|err| tracing::warn!("invalid ignore regex `{pattern}`: {err}"))
+					.ok()
+			})
+			.collect();
+		self.options = options;
+		self.cache = Mutex::default();
+	}
+
+	/// Replaces the spelling backend and invalidates previous lookups
+	pub fn set_backend(&mut self, backend: Box<dyn SpellBackend>) {
+		self.backend = backend;
+		self.cache = Mutex::default();
+		self.unsupported = Mutex::default();
+	}
+
+	/// Runs `rule` along with the builtin grammar rules
+	pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+		self.rules.push(rule);
+	}
+
+	/// Code of the language used to fragmentize the source
+	#[must_use]
+	pub fn language(&self) -> &'static str {
+		self.processor.fragmentizer.lang_code()
 	}
 }
 
@@ -48,41 +174,321 @@ impl<'a> Checker<'a> {
 impl<'a> Checker<'a> {
 	#[must_use]
 	pub fn check(&self) -> Vec<WordDiagnostic> {
-		let fragments = self.processor.process();
-		let mut diags = Vec::new();
-
-		for token in fragments {
-			match token.kind {
-				TokenKind::Word | TokenKind::Unknown => {
-					let source = self.source.str_from(token.span).to_string();
-					match self.diagnostic(source, token.span) {
-						Some(diag) => diags.push(diag),
-						None => continue,
-					};
+		let fragments = self.processor.process(&self.ignore_regexes);
+		let symbols = self.symbols(&fragments);
+		let mut diags = repeated::find(self.source, &fragments);
+
+		let rules = self
+			.rules
+			.iter()
+			.map(AsRef::as_ref)
+			.filter(|rule| {
+				!self
+					.options
+					.disabled_rules
+					.iter()
+					.any(|code| code == rule.code())
+			})
+			.collect::<Vec<_>>();
+		diags.extend(grammar::check(self.source, &fragments, &rules));
+
+		for TokenizedFragment {
+			fragment,
+			tokens,
+			language,
+			detected,
+			..
+		} in fragments
+		{
+			let language = match language {
+				Some(language) if !self.supports(&language, detected) => {
+					if detected && self.backend.has_language() {
+						continue;
+					}
+					None
 				}
+				language => language,
 			};
+
+			for token in tokens {
+				match token.kind {
+					TokenKind::Word | TokenKind::Unknown => {
+						let source = self.source.str_from(token.span).to_string();
+						// Words are compared in a single form, and reported as written
+						let word = self.options.normalization.apply(&source).into_owned();
+						if !self.options.filters.accepts(&word) {
+							continue;
+						}
+
+						// Prose is allowed to mention identifiers of the document, a
+						// misspelled one is reported where it appears in code
+						if fragment.kind.is_prose() && symbols.contains(&word.to_lowercase()) {
+							continue;
+						}
+
+						let ident = (fragment.kind == FragmentKind::Ident).then_some(fragment.span);
+						if self.lookup(&word, language.as_deref(), ident.is_some()) {
+							continue;
+						}
+
+						// Only comments are corrected automatically, renaming an
+						// identifier or changing a string is left to the user
+						let kind = match misspellings::correction(&word) {
+							Some(correction) if fragment.kind == FragmentKind::Comment => {
+								DiagnosticKind::Typo { correction }
+							}
+							_ => DiagnosticKind::Misspelled,
+						};
+						diags.push(WordDiagnostic {
+							word: source,
+							span: token.span,
+							kind,
+							ident,
+						});
+					}
+					TokenKind::Number => {}
+				};
+			}
 		}
 
 		diags
 	}
 
-	fn diagnostic(&self, word: String, span: Span) -> Option<WordDiagnostic> {
-		if self.lookup(&word) {
-			None
-		} else {
-			Some(WordDiagnostic { word, span })
+	/// Lowercased identifiers of the document and their individual words
+	fn symbols(&self, fragments: &[TokenizedFragment]) -> HashSet<String> {
+		let mut symbols = HashSet::new();
+		for TokenizedFragment {
+			fragment, tokens, ..
+		} in fragments
+		{
+			if fragment.kind != FragmentKind::Ident {
+				continue;
+			}
+
+			let spans = tokens
+				.iter()
+				.filter(|token| token.kind == TokenKind::Word)
+				.map(|token| token.span);
+			for span in spans.chain([fragment.span]) {
+				let symbol = self.source.str_from(span).to_string();
+				symbols.insert(self.options.normalization.apply(&symbol).to_lowercase());
+			}
 		}
+		symbols
 	}
 
-	fn lookup(&self, word: &str) -> bool {
-		let mut cache = self.cache.lock().unwrap();
+	/// Whether a dictionary of `language` is loaded, fragments in other
+	/// languages are checked with every dictionary, or skipped if their
+	/// language was detected while dictionaries of other languages are loaded
+	fn supports(&self, language: &str, detected: bool) -> bool {
+		if self.backend.supports(language) {
+			return true;
+		}
+		if self.unsupported.lock().unwrap().insert(language.to_owned()) {
+			if detected && self.backend.has_language() {
+				tracing::debug!(
+					"no dictionary loaded for detected `{language}`, skipping its prose"
+				);
+			} else {
+				tracing::warn!(
+					"no dictionary loaded for `{language}`, checking with every dictionary"
+				);
+			}
+		}
+		false
+	}
+
+	/// Whether the normalized `word` is correctly spelled, identifier parts
+	/// ignore case as they are often lowercased proper nouns or acronyms, e.g.
+	/// `github_url`
+	fn lookup(&self, word: &str, language: Option<&str>, fold_case: bool) -> bool {
+		let mut caches = self.cache.lock().unwrap();
+		let cache = caches
+			.entry((language.map(ToOwned::to_owned), fold_case))
+			.or_default();
 
 		if let Some(lookup) = cache.get(word) {
-			*lookup
-		} else {
-			let lookup = self.dictionary.lookup(word).unwrap();
-			cache.insert(word.to_owned(), lookup);
-			lookup
+			return *lookup;
+		}
+
+		let lookup = match (language, fold_case) {
+			(None, false) => self.backend.lookup(word),
+			(None, true) => self.backend.lookup_folded(word),
+			(Some(language), false) => self.backend.lookup_in(word, language),
+			(Some(language), true) => self.backend.lookup_folded_in(word, language),
+		};
+		cache.insert(word.to_owned(), lookup);
+		drop(caches);
+		lookup
+	}
+
+	/// Candidates to replace `word` with, its common correction first
+	#[must_use]
+	pub fn suggest(&self, word: &str) -> Vec<String> {
+		let mut suggestions = Vec::from_iter(misspellings::correction(word));
+		for suggestion in self.backend.suggest(word) {
+			if !suggestions.contains(&suggestion) {
+				suggestions.push(suggestion);
+			}
 		}
+		suggestions.truncate(MAX_SUGGESTIONS);
+		suggestions
+	}
+
+	/// Names of the dictionaries words are looked up in
+	#[must_use]
+	pub fn dictionaries(&self) -> Vec<String> {
+		self.backend.dictionaries()
+	}
+
+	/// Accepts `word` in this document from now on
+	pub fn add_word(&mut self, word: &str) {
+		let word = self.options.normalization.apply(word);
+		self.backend.add_word(&word);
+		for cache in self.cache.get_mut().unwrap().values_mut() {
+			cache.remove(word.as_ref());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::wordlist::WordList;
+
+	/// Word list of a single language
+	#[derive(Debug)]
+	struct Dictionary(WordList, &'static str);
+
+	impl SpellBackend for Dictionary {
+		fn lookup(&self, word: &str) -> bool {
+			self.0.contains(word)
+		}
+
+		fn suggest(&self, word: &str) -> Vec<String> {
+			self.0.suggest(word)
+		}
+
+		fn add_word(&mut self, word: &str) {
+			self.0.insert(word);
+		}
+
+		fn language(&self) -> Option<&str> {
+			Some(self.1)
+		}
+	}
+
+	#[test]
+	fn check_unsupported_languages_with_every_dictionary() {
+		let source = Source::new(
+			"// lspelling: lang=fr\n// the speling\n// lspelling: lang=en\n// the speling\n",
+		);
+		let backend = Dictionary(WordList::parse("lspelling\nlang\nfr\nen\nthe"), "en_US");
+		let checker = Checker::new(Box::new(backend), "rust", &source);
+
+		let words = checker
+			.check()
+			.into_iter()
+			.map(|diag| diag.word)
+			.collect::<Vec<_>>();
+		assert_eq!(words, ["speling", "speling"]);
+	}
+
+	#[test]
+	fn skip_detected_languages_without_matching_dictionary() {
+		let source = Source::new(
+			"// Ce commentaire explique pourquoi le cache est invalidé à chaque modification\n\n\
+			 // This comment explains why the cahce is invalidated on every change\n",
+		);
+		let backend = Dictionary(
+			WordList::parse(
+				"this\ncomment\nexplains\nwhy\nthe\nis\ninvalidated\non\nevery\nchange",
+			),
+			"en_US",
+		);
+		let mut checker = Checker::new(Box::new(backend), "rust", &source);
+		checker.set_options(Options {
+			detect_language: true,
+			..Options::default()
+		});
+
+		let words = checker
+			.check()
+			.into_iter()
+			.map(|diag| diag.word)
+			.collect::<Vec<_>>();
+		assert_eq!(words, ["cahce"]);
+	}
+
+	#[test]
+	fn check_detected_languages_with_untagged_dictionaries() {
+		let source =
+			Source::new("// This comment explains why the cahce is invalidated on every change\n");
+		// Named like a dictionary loaded from `custom.dic`, which never supports `en`
+		let backend = Dictionary(
+			WordList::parse(
+				"this\ncomment\nexplains\nwhy\nthe\nis\ninvalidated\non\nevery\nchange",
+			),
+			"custom",
+		);
+		let mut checker = Checker::new(Box::new(backend), "rust", &source);
+		checker.set_options(Options {
+			detect_language: true,
+			..Options::default()
+		});
+
+		let words = checker
+			.check()
+			.into_iter()
+			.map(|diag| diag.word)
+			.collect::<Vec<_>>();
+		assert_eq!(words, ["cahce"]);
+	}
+
+	#[test]
+	fn accept_added_words_in_any_unicode_form() {
+		let source = Source::new("// caf\u{e9} cr\u{e8}me\n");
+		let backend = Dictionary(WordList::default(), "fr");
+		let mut checker = Checker::new(Box::new(backend), "rust", &source);
+		assert_eq!(checker.check().len(), 2);
+
+		// Decomposed, with combining accents
+		checker.add_word("cafe\u{301}");
+		checker.add_word("cre\u{300}me");
+		assert!(checker.check().is_empty());
+	}
+
+	#[test]
+	fn correct_common_misspellings_in_comments_only() {
+		let source = Source::new("// teh occured\nfn seperate() { \"occured\"; }\n");
+		let backend = Dictionary(WordList::parse("fn\nteh"), "en");
+		let checker = Checker::new(Box::new(backend), "rust", &source);
+
+		let diags = checker
+			.check()
+			.into_iter()
+			.map(|diag| (diag.word, diag.kind))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			diags,
+			[
+				(
+					"occured".to_owned(),
+					DiagnosticKind::Typo {
+						correction: "occurred".to_owned()
+					}
+				),
+				("seperate".to_owned(), DiagnosticKind::Misspelled),
+				("occured".to_owned(), DiagnosticKind::Misspelled),
+			]
+		);
+	}
+
+	#[test]
+	fn suggest_common_corrections_first() {
+		let source = Source::new("");
+		let backend = Dictionary(WordList::parse("seperated"), "en");
+		let checker = Checker::new(Box::new(backend), "rust", &source);
+		assert_eq!(checker.suggest("seperate"), ["separate", "seperated"]);
 	}
 }
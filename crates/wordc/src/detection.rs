@@ -0,0 +1,85 @@
+//! Guesses the natural language prose is written in
+
+use whatlang::Lang;
+
+/// Number of letters under which a text is too ambiguous to be identified
+const MIN_LETTERS: usize = 40;
+
+/// Language tag of `text`, as found in dictionary names (e.g. `fr`), if it can
+/// be reliably identified
+pub(crate) fn detect(text: &str) -> Option<&'static str> {
+	if text.chars().filter(|c| c.is_alphabetic()).count() < MIN_LETTERS {
+		return None;
+	}
+
+	let info = whatlang::detect(text)?;
+	if !info.is_reliable() {
+		return None;
+	}
+	language_tag(info.lang())
+}
+
+/// ISO 639-1 code of languages that commonly have a hunspell dictionary
+const fn language_tag(lang: Lang) -> Option<&'static str> {
+	let tag = match lang {
+		Lang::Afr => "af",
+		Lang::Aze => "az",
+		Lang::Bel => "be",
+		Lang::Bul => "bg",
+		Lang::Cat => "ca",
+		Lang::Ces => "cs",
+		Lang::Dan => "da",
+		Lang::Deu => "de",
+		Lang::Ell => "el",
+		Lang::Eng => "en",
+		Lang::Epo => "eo",
+		Lang::Est => "et",
+		Lang::Fin => "fi",
+		Lang::Fra => "fr",
+		Lang::Heb => "he",
+		Lang::Hrv => "hr",
+		Lang::Hun => "hu",
+		Lang::Hye => "hy",
+		Lang::Ind => "id",
+		Lang::Ita => "it",
+		Lang::Kat => "ka",
+		Lang::Lat => "la",
+		Lang::Lav => "lv",
+		Lang::Lit => "lt",
+		Lang::Mkd => "mk",
+		Lang::Nld => "nl",
+		Lang::Nob => "nb",
+		Lang::Pol => "pl",
+		Lang::Por => "pt",
+		Lang::Ron => "ro",
+		Lang::Rus => "ru",
+		Lang::Slk => "sk",
+		Lang::Slv => "sl",
+		Lang::Spa => "es",
+		Lang::Srp => "sr",
+		Lang::Swe => "sv",
+		Lang::Tur => "tr",
+		Lang::Ukr => "uk",
+		Lang::Vie => "vi",
+		_ => return None,
+	};
+	Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn detect_prose_language() {
+		assert_eq!(
+			detect("Ceci est un commentaire écrit en français pour expliquer le code"),
+			Some("fr")
+		);
+		assert_eq!(
+			detect("This comment explains why the cache is invalidated on every change"),
+			Some("en")
+		);
+		assert_eq!(detect("Short one"), None);
+	}
+}
@@ -0,0 +1,226 @@
+//! Instructions written in comments of the checked source, e.g. `// lspelling: lang=fr_FR`
+
+use crate::{
+	normalize::Normalization,
+	span::{BytePos, Source, Span},
+	wordlist::WordList,
+};
+
+/// Prefix of every directive, the space after the colon is optional
+const MARKER: &str = "lspelling:";
+
+/// Openers of comment lines in common languages, `*` continuing block comments
+pub const COMMENT_MARKERS: [&str; 7] = ["//", "/*", "*", "#", "--", ";", "<!--"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Directive {
+	/// Check the following text with dictionaries of this language
+	Lang(String),
+	/// Do not check the line after the comment
+	IgnoreNextLine,
+	/// Accept these words in the whole source
+	Ignore(Vec<String>),
+	/// Stop checking until the next `enable` directive
+	Disable,
+	Enable,
+}
+
+/// Directives of a source, in order of appearance
+#[derive(Debug, Default)]
+pub(crate) struct Directives {
+	/// Directives along with the comment they are written in
+	entries: Vec<(Span, Directive)>,
+	/// Parts of the source that are not checked, including the directives themselves
+	ignored: Vec<Span>,
+	/// Words accepted in the whole source
+	words: WordList,
+}
+
+impl Directives {
+	/// Ignored words are stored in the `normalization` form tokens are compared in
+	pub(crate) fn parse(source: &Source, comments: &[Span], normalization: Normalization) -> Self {
+		let mut entries = comments
+			.iter()
+			.filter_map(|&comment| {
+				let text = source.str_from(comment).to_string();
+				parse_directive(&text).map(|directive| (comment, directive))
+			})
+			.collect::<Vec<_>>();
+		entries.sort_by_key(|(span, _)| span.low);
+
+		let mut ignored = Vec::new();
+		let mut words = WordList::default();
+		let mut disabled_since = None;
+		for (comment, directive) in &entries {
+			ignored.push(*comment);
+			match directive {
+				Directive::Lang(_) => {}
+				Directive::IgnoreNextLine => ignored.extend(next_line(source, *comment)),
+				Directive::Ignore(list) => words.extend(
+					list.iter()
+						.map(|word| normalization.apply(word).into_owned()),
+				),
+				Directive::Disable => {
+					disabled_since.get_or_insert(comment.low);
+				}
+				Directive::Enable => {
+					if let Some(low) = disabled_since.take() {
+						ignored.push(Span::new(low, comment.high));
+					}
+				}
+			}
+		}
+		if let Some(low) = disabled_since {
+			ignored.push(Span::new(low, BytePos::from(source.0.len_bytes())));
+		}
+
+		Self {
+			entries,
+			ignored,
+			words,
+		}
+	}
+
+	/// Whether `span` is in a part of the source that is not checked
+	pub(crate) fn ignores(&self, span: Span) -> bool {
+		self.ignored
+			.iter()
+			.any(|ignored| ignored.low <= span.low && span.high <= ignored.high)
+	}
+
+	/// Parts of the source that are not checked
+	pub(crate) fn ignored(&self) -> &[Span] {
+		&self.ignored
+	}
+
+	/// Whether `word` is accepted by an `ignore` directive
+	pub(crate) fn ignores_word(&self, word: &str) -> bool {
+		self.words.contains(word)
+	}
+
+	/// Language selected by the last `lang` directive before `pos`
+	pub(crate) fn language_at(&self, pos: BytePos) -> Option<&str> {
+		self.entries
+			.iter()
+			.take_while(|(comment, _)| comment.low <= pos)
+			.filter_map(|(_, directive)| match directive {
+				Directive::Lang(language) => Some(language.as_str()),
+				_ => None,
+			})
+			.last()
+	}
+}
+
+/// Whether `c` belongs to a comment opener, e.g. the `!` of `<!--` or `//!`
+pub(crate) fn is_marker_char(c: char) -> bool {
+	COMMENT_MARKERS.iter().any(|marker| marker.contains(c))
+}
+
+/// Comment holding `directive` (e.g. `ignore-next-line`) in the syntax of a language
+#[must_use]
+pub fn comment(language: &str, directive: &str) -> String {
+	let (open, close) = match language {
+		"markdown" | "html" | "xml" | "vue" | "svelte" => ("<!--", " -->"),
+		"css" | "scss" | "less" => ("/*", " */"),
+		"python" | "shellscript" | "toml" | "yaml" | "ruby" | "perl" | "r" | "makefile"
+		| "dockerfile" | "nix" | "elixir" | "git-commit" | "plaintext" => ("#", ""),
+		"lua" | "sql" | "haskell" => ("--", ""),
+		"ini" | "clojure" | "scheme" => (";", ""),
+		_ => ("//", ""),
+	};
+	format!("{open} {MARKER}{directive}{close}")
+}
+
+/// Parses the directive in the text of a comment, if any
+fn parse_directive(comment: &str) -> Option<Directive> {
+	let (_, rest) = comment.split_once(MARKER)?;
+	// Closing delimiters of block comments, e.g. `*/` or `-->`
+	let rest = rest.trim().trim_end_matches("*/").trim_end_matches("-->");
+
+	let mut words = rest.split_whitespace();
+	let directive = match words.next()? {
+		"ignore-next-line" => Directive::IgnoreNextLine,
+		"ignore" => Directive::Ignore(words.map(ToOwned::to_owned).collect()),
+		"disable" => Directive::Disable,
+		"enable" => Directive::Enable,
+		command => match command.split_once('=') {
+			Some(("lang", language)) if !language.is_empty() => {
+				Directive::Lang(language.to_owned())
+			}
+			_ => {
+				tracing::debug!("ignoring unknown directive `{}`", rest.trim());
+				return None;
+			}
+		},
+	};
+	Some(directive)
+}
+
+/// Line following the one `comment` ends on
+fn next_line(source: &Source, comment: Span) -> Option<Span> {
+	// Line comments may include their line break
+	let line = source
+		.0
+		.try_byte_to_line(comment.high.to_usize().checked_sub(1)?)
+		.ok()? + 1;
+	if line >= source.0.len_lines() {
+		return None;
+	}
+
+	Some(Span::new(
+		BytePos::from(source.0.line_to_byte(line)),
+		BytePos::from(source.0.line_to_byte(line + 1)),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_lang_regions() {
+		let source =
+			Source::new("// lspelling: lang=fr_FR\nbonjour\n<!-- lspelling:lang=de -->\nhallo");
+		let comments = [
+			Span::new(BytePos(0), BytePos(24)),
+			Span::new(BytePos(33), BytePos(59)),
+		];
+		let directives = Directives::parse(&source, &comments, Normalization::default());
+
+		assert_eq!(directives.language_at(BytePos(0)), Some("fr_FR"));
+		assert_eq!(directives.language_at(BytePos(25)), Some("fr_FR"));
+		assert_eq!(directives.language_at(BytePos(60)), Some("de"));
+		assert!(directives.ignores(Span::new(BytePos(38), BytePos(47))));
+		assert!(!directives.ignores(Span::new(BytePos(25), BytePos(32))));
+	}
+
+	#[test]
+	fn parse_suppressions() {
+		let text = r"
+			# lspelling:ignore Ferris crabe
+			# lspelling: ignore-next-line
+			teh
+			# lspelling:disable
+			wrod
+			# lspelling:enable
+			last
+		";
+		let source = Source::new(text);
+		let span_of = |part: &str| {
+			let low = text.find(part).unwrap();
+			Span::new(BytePos::from(low), BytePos::from(low + part.len()))
+		};
+		let comments = text
+			.lines()
+			.filter(|line| line.contains('#'))
+			.map(|line| span_of(line.trim_start()))
+			.collect::<Vec<_>>();
+		let directives = Directives::parse(&source, &comments, Normalization::default());
+
+		assert!(directives.ignores_word("Ferris"));
+		assert!(directives.ignores_word("crabe"));
+		assert!(directives.ignores(span_of("teh")));
+		assert!(directives.ignores(span_of("wrod")));
+		assert!(!directives.ignores(span_of("last")));
+	}
+}
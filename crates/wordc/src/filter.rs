@@ -0,0 +1,76 @@
+//! Tokens that are never looked up
+
+use serde::Deserialize;
+
+/// Skips tokens that are too noisy to be checked, every token is checked by
+/// default
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TokenFilters {
+	/// Tokens with fewer characters are skipped, e.g. `2` skips the `x` of
+	/// `x_axis` and `3` the `fs` and `io` of idents
+	pub min_length: usize,
+	/// Skip all-uppercase tokens, e.g. `LSP`, `HTTP` or `URLs`
+	pub acronyms: bool,
+	/// Skip tokens containing digits, e.g. `sha256sum`
+	pub digits: bool,
+}
+
+impl Default for TokenFilters {
+	fn default() -> Self {
+		Self {
+			min_length: 1,
+			acronyms: false,
+			digits: false,
+		}
+	}
+}
+
+impl TokenFilters {
+	/// Whether `word` goes through to the spelling backend
+	#[must_use]
+	pub fn accepts(&self, word: &str) -> bool {
+		let length = word.chars().count();
+
+		!(length < self.min_length
+			|| (self.acronyms && is_acronym(word))
+			|| (self.digits && word.chars().any(char::is_numeric)))
+	}
+}
+
+/// Whether `word` is uppercase, allowing a plural `s`
+fn is_acronym(word: &str) -> bool {
+	let word = word.strip_suffix('s').unwrap_or(word);
+	word.chars().count() > 1 && word.chars().all(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn filter_noisy_tokens() {
+		let words = ["io", "LSP", "URLs", "sha256sum", "x", "Spelling", "wrod"];
+		let filters = TokenFilters::default();
+		assert!(words.iter().all(|word| filters.accepts(word)));
+
+		let filters = TokenFilters {
+			min_length: 3,
+			acronyms: true,
+			digits: true,
+		};
+		let accepted = words
+			.into_iter()
+			.filter(|word| filters.accepts(word))
+			.collect::<Vec<_>>();
+		assert_eq!(accepted, ["Spelling", "wrod"]);
+
+		let filters = TokenFilters {
+			min_length: 2,
+			..TokenFilters::default()
+		};
+		assert!(filters.accepts("io"));
+		assert!(filters.accepts("HTTP"));
+		assert!(!filters.accepts("x"));
+	}
+}
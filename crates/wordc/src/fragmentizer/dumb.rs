@@ -3,7 +3,11 @@ use std::iter::{Enumerate, Peekable};
 use ropey::iter::Chars;
 
 use super::{Fragment, FragmentKind, Fragmentizer};
-use crate::span::{BytePos, Source, Span};
+use crate::{
+	directive::COMMENT_MARKERS,
+	normalize,
+	span::{BytePos, Source, Span},
+};
 
 // TODO: make a context-aware parser with tree-sitter, change checking mode in function of context
 // TODO: make a nom parser to compare speed
@@ -34,25 +38,49 @@ impl<'a> Fragmentizer<'a> for DumbFragmentizer<'a> {
 		let mut buffer = Vec::new();
 		while let Some((start, char_)) = chars.next() {
 			let kind = match char_ {
-				c if c.is_alphanumeric() => Some(Self::cook_word(&mut chars)),
+				c if normalize::is_word_char(c) => Some(Self::cook_word(&mut chars)),
 				_ => {
 					// Ignore special characters
-					Self::eat_while(&mut chars, |c| !char::is_alphanumeric(c));
+					Self::eat_while(&mut chars, |c| !normalize::is_word_char(c));
 					None
 				}
 			};
 
 			if let Some(kind) = kind {
 				// TODO: ugly
+				let end = chars.peek().map_or(max_chars, |(pos, _)| *pos);
 				let span = Span::new(
-					BytePos::from(start),
-					BytePos::from(chars.peek().map_or(max_chars, |(pos, _)| *pos)),
+					BytePos::from(self.source.0.char_to_byte(start)),
+					BytePos::from(self.source.0.char_to_byte(end)),
 				);
 				buffer.push(Fragment { kind, span });
 			};
 		}
 		buffer
 	}
+
+	/// Fragments are plain words, comments are found line by line
+	fn comments(&self, _fragments: &[Fragment]) -> Vec<Span> {
+		let mut comments = Vec::new();
+		for (index, line) in self.source.0.lines().enumerate() {
+			let line = line.to_string();
+			let line = line.trim_end_matches(['\n', '\r']);
+			let Some(start) = COMMENT_MARKERS
+				.iter()
+				.filter_map(|marker| line.find(marker))
+				.min()
+			else {
+				continue;
+			};
+
+			let line_start = self.source.0.line_to_byte(index);
+			comments.push(Span::new(
+				BytePos::from(line_start + start),
+				BytePos::from(line_start + line.len()),
+			));
+		}
+		comments
+	}
 }
 
 impl DumbFragmentizer<'_> {
@@ -67,7 +95,38 @@ impl DumbFragmentizer<'_> {
 
 impl DumbFragmentizer<'_> {
 	fn cook_word(chars: &mut Peekable<Enumerate<Chars>>) -> FragmentKind {
-		Self::eat_while(chars, char::is_alphanumeric);
+		Self::eat_while(chars, normalize::is_word_char);
 		FragmentKind::Unknown
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn byte_spans_after_multibyte_chars() {
+		let source = Source::new("déjà — vu");
+		let fragmentizer = DumbFragmentizer::new(&source);
+
+		let words = fragmentizer
+			.fragmentize()
+			.into_iter()
+			.map(|fragment| source.str_from(fragment.span).to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(words, ["déjà", "vu"]);
+	}
+
+	#[test]
+	fn keep_combining_marks_in_words() {
+		let source = Source::new("cafe\u{301} cre\u{300}me");
+		let fragmentizer = DumbFragmentizer::new(&source);
+
+		let words = fragmentizer
+			.fragmentize()
+			.into_iter()
+			.map(|fragment| source.str_from(fragment.span).to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(words, ["cafe\u{301}", "cre\u{300}me"]);
+	}
+}
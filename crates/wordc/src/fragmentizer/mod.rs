@@ -1,3 +1,4 @@
+use serde::Deserialize;
 use std::fmt;
 
 use crate::span::Span;
@@ -12,6 +13,27 @@ pub(crate) trait Fragmentizer<'a>: Send + Sync + fmt::Debug {
 	fn lang_code(&self) -> &'static str;
 
 	fn fragmentize(&self) -> Vec<Fragment>;
+
+	/// Spans of the comments, where directives are looked for
+	fn comments(&self, fragments: &[Fragment]) -> Vec<Span> {
+		fragments
+			.iter()
+			.filter(|fragment| fragment.kind == FragmentKind::Comment)
+			.map(|fragment| fragment.span)
+			.collect()
+	}
+}
+
+/// Which identifiers are reported by code-aware fragmentizers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentifierMode {
+	/// Every identifier, including uses of items defined elsewhere
+	#[default]
+	All,
+	/// Only identifiers where they are declared, e.g. `let` bindings, function
+	/// names and parameters or type and field names
+	Definitions,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -26,10 +48,20 @@ pub(crate) enum FragmentKind {
 	// Inner string contains a composed? word with a code case (snake, upper, camel, etc.)
 	Ident,
 
-	// Could be either a string literal or a comment
+	// A string literal
 	// The inner string contains text with spaces
 	Sentence,
 
+	// A comment, prose like sentences
+	Comment,
+
 	// String that should be alone with no parsing semantics
 	Unknown,
 }
+
+impl FragmentKind {
+	/// Whether the fragment is text with spaces rather than code
+	pub(crate) const fn is_prose(&self) -> bool {
+		matches!(self, Self::Sentence | Self::Comment)
+	}
+}
@@ -1,17 +1,32 @@
 use std::{
+	collections::HashSet,
 	fmt,
 	ops::{Range, RangeBounds},
 };
-use tree_sitter::{Parser, Query, QueryCapture, QueryCursor, QueryMatch, Tree};
+use tree_sitter::{Node, Parser, Query, QueryCapture, QueryCursor, QueryMatch, Tree};
 
-use super::{Fragment, Fragmentizer};
+use super::{Fragment, Fragmentizer, IdentifierMode};
 use crate::{
+	checker::Options,
 	fragmentizer::FragmentKind,
 	span::{BytePos, Source, Span},
 };
 
+/// Crates that are always in scope
+const PRELUDE_CRATES: [&str; 3] = ["std", "core", "alloc"];
+
+/// Path roots that never name another crate
+const LOCAL_ROOTS: [&str; 21] = [
+	"self", "super", "crate", "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize",
+	"i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64", "f16",
+];
+
 pub(crate) struct RustFragmentizer<'a> {
 	source: &'a Source,
+	identifiers: IdentifierMode,
+	ignore_imports: bool,
+	/// Crates the source depends on
+	crates: HashSet<String>,
 
 	parser: Parser,
 	query: Query,
@@ -28,7 +43,7 @@ impl fmt::Debug for RustFragmentizer<'_> {
 pub const SPELLCHECK_QUERY: &str = include_str!("../../queries/rust.scm");
 
 impl<'a> RustFragmentizer<'a> {
-	pub(crate) fn new(source: &'a Source) -> Self {
+	pub(crate) fn new(source: &'a Source, options: &Options) -> Self {
 		let grammar = tree_sitter_rust::language();
 
 		let mut parser = Parser::new();
@@ -40,6 +55,9 @@ impl<'a> RustFragmentizer<'a> {
 
 		Self {
 			source,
+			identifiers: options.identifiers,
+			ignore_imports: options.ignore_imports,
+			crates: options.crates.iter().cloned().collect(),
 
 			parser,
 			query,
@@ -63,32 +81,195 @@ impl<'a> Fragmentizer<'a> for RustFragmentizer<'a> {
 		let matches = cursor.matches(&self.query, self.tree.root_node(), source.as_bytes());
 		let patterns = self.query.capture_names();
 
-		let capture_to_fragment = |match_: &QueryMatch, capture: &QueryCapture| {
+		let imported = if self.ignore_imports {
+			Imports::new(source.as_bytes(), &self.crates).collect(self.tree.root_node())
+		} else {
+			HashSet::new()
+		};
+
+		let capture_to_fragment = |capture: &QueryCapture| {
 			let Range { start, end } = capture.node.byte_range();
 			let span = Span::new(BytePos::from(start), BytePos::from(end));
 
-			// TODO: pattern index doesn't match patterns map in any circonstances
-			let kind = match patterns[match_.pattern_index] {
-				"ident" => FragmentKind::Ident,
-				"sentence.string" | "sentence.comment" => FragmentKind::Sentence,
+			let kind = match (patterns[capture.index as usize], self.identifiers) {
+				("ident", IdentifierMode::All)
+				| ("ident.definition", IdentifierMode::Definitions) => {
+					if imported.contains(&source[start..end]) {
+						return None;
+					}
+					FragmentKind::Ident
+				}
+				("ident" | "ident.definition", _) => return None,
+				("sentence.string", _) => FragmentKind::Sentence,
+				("sentence.comment", _) => FragmentKind::Comment,
 				_ => unreachable!("this part is kept in sync with query"),
 			};
 
-			Fragment { kind, span }
+			Some(Fragment { kind, span })
 		};
 
 		matches
-			.flat_map(|match_| {
+			.flat_map(|match_: QueryMatch| {
 				match_
 					.captures
 					.iter()
-					.map(|capture| capture_to_fragment(&match_, capture))
+					.filter_map(capture_to_fragment)
 					.collect::<Vec<_>>()
 			})
 			.collect()
 	}
 }
 
+/// Collects names that come from other crates, either brought in scope by
+/// `use` declarations or referred to with a path, e.g. `tower_lsp::Client`
+struct Imports<'s> {
+	source: &'s [u8],
+	/// Crates the source depends on
+	crates: &'s HashSet<String>,
+	/// Modules declared in this file, paths starting with them are local
+	local_modules: HashSet<&'s str>,
+	names: HashSet<String>,
+}
+
+impl<'s> Imports<'s> {
+	fn new(source: &'s [u8], crates: &'s HashSet<String>) -> Self {
+		Self {
+			source,
+			crates,
+			local_modules: HashSet::new(),
+			names: HashSet::new(),
+		}
+	}
+
+	fn collect(mut self, root: Node) -> HashSet<String> {
+		self.local_modules = children(root)
+			.filter(|node| node.kind() == "mod_item")
+			.filter_map(|node| node.child_by_field_name("name"))
+			.filter_map(|name| name.utf8_text(self.source).ok())
+			.collect();
+
+		self.collect_uses(root);
+		self.collect_paths(root);
+		self.names
+	}
+
+	fn collect_uses(&mut self, node: Node) {
+		for child in children(node) {
+			match child.kind() {
+				"use_declaration" => {
+					if let Some(argument) = child.child_by_field_name("argument") {
+						self.collect_use_tree(argument, None);
+					}
+				}
+				"extern_crate_declaration" => {
+					if let Some(name) = child.child_by_field_name("name") {
+						self.insert_idents(name);
+					}
+				}
+				_ => self.collect_uses(child),
+			}
+		}
+	}
+
+	/// `external` is known when the tree is nested in a list, e.g. `b` in `a::{b, c}`
+	fn collect_use_tree(&mut self, node: Node, external: Option<bool>) {
+		match node.kind() {
+			"identifier" | "scoped_identifier"
+				if external.unwrap_or_else(|| self.is_external(node, true)) =>
+			{
+				self.insert_idents(node);
+			}
+			// The alias is a local name
+			"use_as_clause" | "use_wildcard" => {
+				if let Some(path) = node
+					.child_by_field_name("path")
+					.or_else(|| node.named_child(0))
+				{
+					self.collect_use_tree(path, external);
+				}
+			}
+			"scoped_use_list" => {
+				let external = node.child_by_field_name("path").map_or(Some(true), |path| {
+					Some(external.unwrap_or_else(|| self.is_external(path, true)))
+				});
+				if let Some(path) = node.child_by_field_name("path") {
+					self.collect_use_tree(path, external);
+				}
+				if let Some(list) = node.child_by_field_name("list") {
+					self.collect_use_tree(list, external);
+				}
+			}
+			"use_list" => {
+				for child in children(node) {
+					self.collect_use_tree(child, external);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Collects segments of paths that start with another crate or an imported name
+	fn collect_paths(&mut self, node: Node) {
+		for child in children(node) {
+			match child.kind() {
+				"use_declaration" => {}
+				"scoped_identifier" | "scoped_type_identifier"
+					if self.is_external(child, false) =>
+				{
+					self.insert_idents(child);
+				}
+				_ => self.collect_paths(child),
+			}
+		}
+	}
+
+	/// Whether `path` starts with another crate
+	///
+	/// Outside of `use` declarations, only crates of the manifest and names
+	/// imported beforehand are known to be external, e.g. `str::trim` is local.
+	fn is_external(&self, path: Node, in_use: bool) -> bool {
+		let mut root = path;
+		while matches!(root.kind(), "scoped_identifier" | "scoped_type_identifier") {
+			match root.child_by_field_name("path") {
+				Some(path) => root = path,
+				// Leading `::`, e.g. `::tokio::spawn`
+				None => return true,
+			}
+		}
+
+		if root.kind() != "identifier" {
+			// `crate`, `self`, `super`, generic or qualified types
+			return false;
+		}
+
+		let Ok(name) = root.utf8_text(self.source) else {
+			return false;
+		};
+		if LOCAL_ROOTS.contains(&name) || self.local_modules.contains(name) {
+			return false;
+		}
+		PRELUDE_CRATES.contains(&name)
+			|| self.names.contains(name)
+			|| self.crates.contains(name)
+			|| (in_use && name.starts_with(char::is_lowercase))
+	}
+
+	fn insert_idents(&mut self, node: Node) {
+		if matches!(node.kind(), "identifier" | "type_identifier") {
+			if let Ok(name) = node.utf8_text(self.source) {
+				self.names.insert(name.to_owned());
+			}
+		}
+		for child in children(node) {
+			self.insert_idents(child);
+		}
+	}
+}
+
+fn children(node: Node) -> impl Iterator<Item = Node> {
+	(0..node.named_child_count()).filter_map(move |index| node.named_child(index))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -100,7 +281,81 @@ mod tests {
 
 		assert_eq!(
 			query.capture_names(),
-			["ident", "sentence.string", "sentence.comment"]
+			[
+				"ident",
+				"ident.definition",
+				"sentence.string",
+				"sentence.comment"
+			]
+		);
+	}
+
+	#[test]
+	fn only_definitions() {
+		let source = Source::new(
+			"
+			struct Wrapper { inner: Rope }
+			fn wrap(rope: Rope) -> Wrapper { let wrapper = Wrapper { inner: rope }; wrapper }
+			",
+		);
+		let options = Options {
+			identifiers: IdentifierMode::Definitions,
+			..Options::default()
+		};
+		let fragmentizer = RustFragmentizer::new(&source, &options);
+
+		let idents = fragmentizer
+			.fragmentize()
+			.into_iter()
+			.filter(|fragment| fragment.kind == FragmentKind::Ident)
+			.map(|fragment| source.str_from(fragment.span).to_string())
+			.collect::<Vec<_>>();
+
+		assert_eq!(idents, ["Wrapper", "inner", "wrap", "rope", "wrapper"]);
+	}
+
+	#[test]
+	fn ignore_external_imports() {
+		let source = Source::new(
+			"
+			use tower_lsp::{lsp_types::Uri, Client as Clyent};
+			use crate::debounce::CheckedDocment;
+			fn open(uri: Uri, client: Clyent) { ropey::Rope::new(); tokio::spawn(); }
+			fn trim(line: &str) { str::trim(line); }
+			",
+		);
+		let options = Options {
+			ignore_imports: true,
+			crates: vec!["ropey".into()],
+			..Options::default()
+		};
+		let fragmentizer = RustFragmentizer::new(&source, &options);
+
+		let idents = fragmentizer
+			.fragmentize()
+			.into_iter()
+			.filter(|fragment| fragment.kind == FragmentKind::Ident)
+			.map(|fragment| source.str_from(fragment.span).to_string())
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			idents,
+			[
+				"Clyent",
+				"debounce",
+				"CheckedDocment",
+				"open",
+				"uri",
+				"client",
+				"Clyent",
+				"tokio",
+				"spawn",
+				"trim",
+				"line",
+				"str",
+				"trim",
+				"line",
+			]
 		);
 	}
 }
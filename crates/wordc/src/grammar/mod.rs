@@ -0,0 +1,202 @@
+//! Rules linting whole sentences rather than single words, e.g. `a`/`an` agreement
+
+use std::{fmt, ops::Range};
+
+use crate::{
+	checker::{DiagnosticKind, WordDiagnostic},
+	directive,
+	fragmentizer::FragmentKind,
+	processor::TokenizedFragment,
+	span::{BytePos, Source, Span},
+};
+
+mod rules;
+
+/// A pattern rule run over every comment
+pub trait Rule: Send + Sync + fmt::Debug {
+	/// Identifier of the rule, e.g. `a-an`, used as the code of its diagnostics
+	fn code(&self) -> &'static str;
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch>;
+}
+
+/// Rules shipped with lspelling
+#[must_use]
+pub fn builtin() -> Vec<Box<dyn Rule>> {
+	rules::builtin()
+}
+
+/// Text of a comment
+#[derive(Debug)]
+pub struct Sentence {
+	pub text: String,
+	pub words: Vec<Word>,
+	/// Whether the line before holds no comment of the same kind, i.e. this
+	/// starts a comment block
+	pub starts_block: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+	/// Byte offset in the sentence text
+	pub offset: usize,
+	pub text: String,
+}
+
+impl Word {
+	#[must_use]
+	pub const fn range(&self) -> Range<usize> {
+		self.offset..self.offset + self.text.len()
+	}
+}
+
+impl Sentence {
+	#[must_use]
+	pub fn new(text: String, starts_block: bool) -> Self {
+		let mut words = Vec::new();
+		let mut start = None;
+		for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+			// Apostrophes are kept inside words, e.g. `it's`
+			let in_word = c.is_alphanumeric() || (start.is_some() && matches!(c, '\'' | '’'));
+			match (in_word, start) {
+				(true, None) => start = Some(index),
+				(false, Some(offset)) => {
+					let word = text[offset..index].trim_end_matches(['\'', '’']);
+					words.push(Word {
+						offset,
+						text: word.to_owned(),
+					});
+					start = None;
+				}
+				_ => {}
+			}
+		}
+
+		Self {
+			text,
+			words,
+			starts_block,
+		}
+	}
+
+	/// Consecutive words only separated by spaces
+	pub fn adjacent_words(&self) -> impl Iterator<Item = (&Word, &Word)> {
+		self.words.windows(2).filter_map(|pair| {
+			let [first, second] = pair else {
+				return None;
+			};
+			let gap = &self.text[first.range().end..second.offset];
+			gap.chars()
+				.all(|c| matches!(c, ' ' | '\t'))
+				.then_some((first, second))
+		})
+	}
+
+	/// Character right after `word`
+	#[must_use]
+	pub fn char_after(&self, word: &Word) -> Option<char> {
+		self.text[word.range().end..].chars().next()
+	}
+}
+
+/// Part of a sentence reported by a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+	/// Byte range in the sentence text
+	pub range: Range<usize>,
+	pub message: String,
+	/// Text to replace the range with, if the fix is unambiguous
+	pub replacement: Option<String>,
+}
+
+/// Runs `rules` over the comments, string literals are often not prose
+pub(crate) fn check(
+	source: &Source,
+	fragments: &[TokenizedFragment],
+	rules: &[&dyn Rule],
+) -> Vec<WordDiagnostic> {
+	let sentences = fragments
+		.iter()
+		.filter(|fragment| fragment.fragment.kind == FragmentKind::Comment);
+
+	let mut diags = Vec::new();
+	for fragment in sentences {
+		let span = fragment.fragment.span;
+		let text = source.str_from(span).to_string();
+		let sentence = Sentence::new(text, starts_block(source, span));
+
+		for rule in rules {
+			for found in rule.check(&sentence) {
+				let matched = Span::new(
+					span.low + BytePos::from(found.range.start),
+					span.low + BytePos::from(found.range.end),
+				);
+				let kind = DiagnosticKind::Grammar {
+					code: rule.code(),
+					message: found.message,
+					replacement: found.replacement,
+				};
+				// Doc comments may be captured as several fragments
+				let reported = diags
+					.iter()
+					.any(|diag: &WordDiagnostic| diag.span == matched && diag.kind == kind);
+				if reported
+					|| fragment.unchecked.iter().any(|unchecked| {
+						unchecked.low < matched.high && matched.low < unchecked.high
+					}) {
+					continue;
+				}
+
+				diags.push(WordDiagnostic {
+					word: sentence.text[found.range].to_owned(),
+					span: matched,
+					kind,
+					ident: None,
+				});
+			}
+		}
+	}
+	diags
+}
+
+/// Whether the line before `span` does not start with the same comment opener
+fn starts_block(source: &Source, span: Span) -> bool {
+	let opener = source
+		.str_from(span)
+		.chars()
+		.take_while(|&c| directive::is_marker_char(c))
+		.collect::<String>();
+	let line = source.0.byte_to_line(span.low.to_usize());
+	if opener.is_empty() || line == 0 {
+		return true;
+	}
+
+	!source
+		.0
+		.line(line - 1)
+		.to_string()
+		.trim_start()
+		.starts_with(&opener)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{checker::Options, processor::FragmentProcessor};
+
+	#[test]
+	fn check_comments_once() {
+		let source = Source::new("/// a apple\nfn main() { \"a apple\"; }\n");
+		let processor = FragmentProcessor::from_lang("rust", &source, &Options::default());
+		let rules = builtin();
+		let rules = rules
+			.iter()
+			.map(AsRef::as_ref)
+			.filter(|rule| rule.code() == "a-an")
+			.collect::<Vec<_>>();
+
+		let diags = check(&source, &processor.process(&[]), &rules);
+		assert_eq!(diags.len(), 1);
+		assert_eq!(diags[0].span, Span::new(BytePos(4), BytePos(5)));
+	}
+}
@@ -0,0 +1,295 @@
+//! Builtin grammar rules
+
+use super::{Rule, RuleMatch, Sentence, Word};
+use crate::casing::{capitalize, match_case};
+
+pub(super) fn builtin() -> Vec<Box<dyn Rule>> {
+	vec![
+		Box::new(ArticleAgreement),
+		Box::new(ItsItIs),
+		Box::new(ThenThan),
+		Box::new(TheirThere),
+		Box::new(DoubledPunctuation),
+		Box::new(DocCapital),
+	]
+}
+
+/// Words starting with a vowel letter but a consonant sound, e.g. `a user`
+const CONSONANT_SOUND_PREFIXES: [&str; 8] =
+	["uni", "use", "usu", "uti", "eu", "one", "once", "ubi"];
+/// Words starting with a consonant letter but a vowel sound, e.g. `an hour`
+const VOWEL_SOUND_PREFIXES: [&str; 4] = ["hour", "honest", "honor", "heir"];
+/// Primitive types mentioned without backticks, which are read in many ways,
+/// e.g. `a usize` or `an usize`
+const PRIMITIVE_TYPES: [&str; 5] = ["usize", "isize", "str", "bool", "char"];
+
+/// `a` before consonant sounds and `an` before vowel sounds
+#[derive(Debug)]
+struct ArticleAgreement;
+
+impl Rule for ArticleAgreement {
+	fn code(&self) -> &'static str {
+		"a-an"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		sentence
+			.adjacent_words()
+			.filter_map(|(article, next)| {
+				let expected = match article.text.to_lowercase().as_str() {
+					"a" | "an" => article_for(&next.text)?,
+					_ => return None,
+				};
+				(!article.text.eq_ignore_ascii_case(expected)).then(|| RuleMatch {
+					range: article.range(),
+					message: format!("Use `{expected}` before `{}`", next.text),
+					replacement: Some(match_case(&article.text, expected)),
+				})
+			})
+			.collect()
+	}
+}
+
+/// Article to use before `word`, if its first sound is known
+fn article_for(word: &str) -> Option<&'static str> {
+	// Acronyms and code are read in many ways, e.g. `an SQL` or `a SQL`, and
+	// `a u8` or `an u8`
+	if word.chars().all(|c| c.is_uppercase() || c.is_numeric())
+		|| word.chars().any(char::is_numeric)
+		|| PRIMITIVE_TYPES.contains(&word)
+	{
+		return None;
+	}
+
+	let lowercase = word.to_lowercase();
+	let vowel_sound = if CONSONANT_SOUND_PREFIXES
+		.iter()
+		.any(|prefix| lowercase.starts_with(prefix))
+	{
+		false
+	} else if VOWEL_SOUND_PREFIXES
+		.iter()
+		.any(|prefix| lowercase.starts_with(prefix))
+	{
+		true
+	} else {
+		lowercase.starts_with(['a', 'e', 'i', 'o', 'u'])
+	};
+	Some(if vowel_sound { "an" } else { "a" })
+}
+
+/// `its` is possessive, `it's` stands for `it is` or `it has`
+#[derive(Debug)]
+struct ItsItIs;
+
+/// Words after which `its` is meant to be `it's`, e.g. `its been`
+const AFTER_IT_IS: [&str; 7] = ["a", "an", "been", "not", "going", "possible", "important"];
+
+impl Rule for ItsItIs {
+	fn code(&self) -> &'static str {
+		"its-it-is"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		sentence
+			.adjacent_words()
+			.filter_map(|(first, second)| {
+				let next = second.text.to_lowercase();
+				let expected = match first.text.to_lowercase().as_str() {
+					"its" if AFTER_IT_IS.contains(&next.as_str()) => "it's",
+					"it's" | "it’s" if next == "own" => "its",
+					_ => return None,
+				};
+				Some(replacement(
+					first,
+					expected,
+					format!("Use `{expected}` here"),
+				))
+			})
+			.collect()
+	}
+}
+
+/// `than` compares, `then` is about time
+#[derive(Debug)]
+struct ThenThan;
+
+const COMPARATIVES: [&str; 15] = [
+	"more", "less", "rather", "other", "fewer", "greater", "better", "worse", "larger", "smaller",
+	"higher", "lower", "longer", "shorter", "faster",
+];
+
+impl Rule for ThenThan {
+	fn code(&self) -> &'static str {
+		"then-than"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		sentence
+			.adjacent_words()
+			.filter(|(comparative, then)| {
+				then.text.eq_ignore_ascii_case("then")
+					&& COMPARATIVES.contains(&comparative.text.to_lowercase().as_str())
+			})
+			.map(|(comparative, then)| {
+				replacement(
+					then,
+					"than",
+					format!("Use `than` to compare after `{}`", comparative.text),
+				)
+			})
+			.collect()
+	}
+}
+
+/// `their` is possessive, `there` a place and `they're` stands for `they are`
+#[derive(Debug)]
+struct TheirThere;
+
+impl Rule for TheirThere {
+	fn code(&self) -> &'static str {
+		"their-there"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		sentence
+			.adjacent_words()
+			.filter_map(|(first, second)| {
+				let next = second.text.to_lowercase();
+				let expected = match first.text.to_lowercase().as_str() {
+					"their" | "they're" | "they’re"
+						if matches!(next.as_str(), "is" | "are" | "was" | "were") =>
+					{
+						"there"
+					}
+					"there" | "they're" | "they’re" if next == "own" => "their",
+					_ => return None,
+				};
+				Some(replacement(
+					first,
+					expected,
+					format!("Use `{expected}` here"),
+				))
+			})
+			.collect()
+	}
+}
+
+/// Punctuation written twice, e.g. `,,`, leaving out ellipses and code like `0..10`
+#[derive(Debug)]
+struct DoubledPunctuation;
+
+impl Rule for DoubledPunctuation {
+	fn code(&self) -> &'static str {
+		"doubled-punctuation"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		let chars = sentence.text.char_indices().collect::<Vec<_>>();
+		chars
+			.windows(4)
+			.filter_map(|window| {
+				let [(_, before), (index, first), (_, second), (_, after)] = *window else {
+					return None;
+				};
+				let doubled = first == second
+					&& matches!(first, ',' | ';' | '.')
+					&& before.is_alphabetic()
+					&& after.is_whitespace();
+				doubled.then(|| RuleMatch {
+					range: index..index + 2,
+					message: format!("`{first}` is doubled"),
+					replacement: Some(first.to_string()),
+				})
+			})
+			.collect()
+	}
+}
+
+/// Doc comments start with a capital letter
+#[derive(Debug)]
+struct DocCapital;
+
+impl Rule for DocCapital {
+	fn code(&self) -> &'static str {
+		"doc-capital"
+	}
+
+	fn check(&self, sentence: &Sentence) -> Vec<RuleMatch> {
+		let text = sentence.text.as_str();
+		let is_doc = (text.starts_with("///") && !text.starts_with("////"))
+			|| text.starts_with("//!")
+			|| (text.starts_with("/**") && !text.starts_with("/***"));
+		if !is_doc || !sentence.starts_block {
+			return Vec::new();
+		}
+
+		let Some(first) = sentence.words.first() else {
+			return Vec::new();
+		};
+		// Code, e.g. `foo()` or `foo_bar`, keeps its case
+		let is_code = text[..first.offset].contains(['`', '['])
+			|| sentence
+				.char_after(first)
+				.is_some_and(|c| matches!(c, '(' | '_' | ':' | '<' | '.' | '`' | '['));
+		if is_code || !first.text.starts_with(char::is_lowercase) {
+			return Vec::new();
+		}
+
+		vec![RuleMatch {
+			range: first.range(),
+			message: "Doc comments start with a capital letter".to_owned(),
+			replacement: Some(capitalize(&first.text)),
+		}]
+	}
+}
+
+/// Match replacing `word` with `expected`, in the case of `word`
+fn replacement(word: &Word, expected: &str, message: String) -> RuleMatch {
+	RuleMatch {
+		range: word.range(),
+		message,
+		replacement: Some(match_case(&word.text, expected)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fixes(text: &str) -> Vec<(&'static str, String, Option<String>)> {
+		let sentence = Sentence::new(text.to_owned(), true);
+		builtin()
+			.iter()
+			.flat_map(|rule| {
+				rule.check(&sentence)
+					.into_iter()
+					.map(|found| (rule.code(), text[found.range].to_owned(), found.replacement))
+			})
+			.collect()
+	}
+
+	#[test]
+	fn builtin_rules() {
+		let text = "/// returns a error,, an user and a hour; its been longer then there own";
+		assert_eq!(
+			fixes(text),
+			[
+				("a-an", "a".into(), Some("an".into())),
+				("a-an", "an".into(), Some("a".into())),
+				("a-an", "a".into(), Some("an".into())),
+				("its-it-is", "its".into(), Some("it's".into())),
+				("then-than", "then".into(), Some("than".into())),
+				("their-there", "there".into(), Some("their".into())),
+				("doubled-punctuation", ",,".into(), Some(",".into())),
+				("doc-capital", "returns".into(), Some("Returns".into())),
+			]
+		);
+
+		let text = "/// `foo()` is an URL... An SQL query is better than 0..10";
+		assert_eq!(fixes(text), []);
+
+		let text = "/// Stores a usize, a u8 and a `i32`";
+		assert_eq!(fixes(text), []);
+	}
+}
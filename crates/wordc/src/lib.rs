@@ -1,4 +1,15 @@
+pub mod backend;
+pub mod casing;
 pub mod checker;
+pub mod detection;
+pub mod directive;
+pub mod filter;
 pub mod fragmentizer;
+pub mod grammar;
+pub mod normalize;
 pub mod processor;
+mod repeated;
 pub mod span;
+mod suggest;
+pub mod vocabulary;
+pub mod wordlist;
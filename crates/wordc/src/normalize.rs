@@ -0,0 +1,65 @@
+//! Unicode normalization of tokens before they are looked up
+
+use serde::Deserialize;
+use std::borrow::Cow;
+use unicode_normalization::{
+	char::is_combining_mark, is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization,
+};
+
+/// Form tokens are normalized to, so that the same word typed differently
+/// gets the same lookup
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+	/// Look tokens up as written
+	None,
+	/// Compose accents, e.g. `e` followed by a combining acute accent becomes `é`
+	#[default]
+	Nfc,
+	/// Like [`Nfc`](Self::Nfc), also folding compatibility characters, e.g.
+	/// ligatures like `ﬁ` or full-width letters
+	Nfkc,
+}
+
+impl Normalization {
+	/// `word` in this normal form, borrowed if it already is
+	#[must_use]
+	pub fn apply(self, word: &str) -> Cow<'_, str> {
+		let is_normalized = match self {
+			Self::None => return Cow::Borrowed(word),
+			Self::Nfc => is_nfc_quick(word.chars()),
+			Self::Nfkc => is_nfkc_quick(word.chars()),
+		};
+		if is_normalized == IsNormalized::Yes {
+			return Cow::Borrowed(word);
+		}
+
+		Cow::Owned(match self {
+			Self::Nfkc => word.nfkc().collect(),
+			_ => word.nfc().collect(),
+		})
+	}
+}
+
+/// Whether `c` belongs to a word, including the combining marks of decomposed
+/// letters, e.g. the accent of `e\u{301}`
+pub(crate) fn is_word_char(c: char) -> bool {
+	c.is_alphanumeric() || is_combining_mark(c)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalize_forms() {
+		let decomposed = "cafe\u{301}";
+		assert_eq!(Normalization::None.apply(decomposed), decomposed);
+		assert_eq!(Normalization::Nfc.apply(decomposed), "café");
+		assert!(matches!(Normalization::Nfc.apply("café"), Cow::Borrowed(_)));
+
+		assert_eq!(Normalization::Nfc.apply("\u{fb01}le"), "\u{fb01}le");
+		assert_eq!(Normalization::Nfkc.apply("\u{fb01}le"), "file");
+		assert_eq!(Normalization::Nfkc.apply("ｗｏｒｄ"), "word");
+	}
+}
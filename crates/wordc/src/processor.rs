@@ -1,5 +1,12 @@
+use regex::Regex;
+use unicode_normalization::char::is_combining_mark;
+
 use crate::{
-	fragmentizer::{DumbFragmentizer, FragmentKind, Fragmentizer, RustFragmentizer},
+	checker::Options,
+	detection,
+	directive::Directives,
+	fragmentizer::{DumbFragmentizer, Fragment, FragmentKind, Fragmentizer, RustFragmentizer},
+	normalize::{self, Normalization},
 	span::{BytePos, Source, Span},
 };
 
@@ -23,20 +30,37 @@ impl Token {
 	}
 }
 
-// TODO: we only support hunspell which is a single word checker
-// we could extend to more complex strategies to lint sentences as a whole
+// TODO: we only support hunspell which is a single word checker, sentences
+// as a whole are linted by `grammar` rules
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum TokenKind {
 	Word,
+	/// Numbers, ordinals, versions and quantities with a unit, never checked
+	Number,
 	Unknown,
 }
 
+/// A fragment along with the tokens it was split into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TokenizedFragment {
+	pub(crate) fragment: Fragment,
+	pub(crate) tokens: Vec<Token>,
+	/// Language selected by a directive for this part of the source
+	pub(crate) language: Option<String>,
+	/// Whether `language` was detected rather than selected by a directive
+	pub(crate) detected: bool,
+	/// Parts of the fragment cut out by ignore regexes or directives
+	pub(crate) unchecked: Vec<Span>,
+}
+
 // ----
 
 #[derive(Debug)]
 pub(crate) struct FragmentProcessor<'a> {
 	pub(crate) fragmentizer: Box<dyn Fragmentizer<'a> + 'a>,
 	source: &'a Source,
+	detect_language: bool,
+	normalization: Normalization,
 }
 
 impl<'a> FragmentProcessor<'a> {
@@ -44,13 +68,15 @@ impl<'a> FragmentProcessor<'a> {
 		Self {
 			fragmentizer,
 			source,
+			detect_language: false,
+			normalization: Normalization::default(),
 		}
 	}
 
 	// TODO: rename
-	pub(crate) fn from_lang(language: &str, source: &'a Source) -> Self {
+	pub(crate) fn from_lang(language: &str, source: &'a Source, options: &Options) -> Self {
 		let fragmentizer: Box<dyn Fragmentizer<'a> + 'a> = match language {
-			"rust" => RustFragmentizer::new(source).boxed(),
+			"rust" => RustFragmentizer::new(source, options).boxed(),
 			"plaintext" => DumbFragmentizer::new(source).boxed(),
 			lang => {
 				tracing::warn!("language `{lang}` is not listed, defaulting to dumb fragmentizer");
@@ -58,31 +84,94 @@ impl<'a> FragmentProcessor<'a> {
 			}
 		};
 
-		Self::new(fragmentizer, source)
+		Self {
+			detect_language: options.detect_language,
+			normalization: options.normalization,
+			..Self::new(fragmentizer, source)
+		}
 	}
 
 	// TODO: op for keeping sentence as is? useful for other strategies than ruspell
-	pub(crate) fn process(&self) -> Vec<Token> {
-		let mut tokens = Vec::new();
-		for fragment in self.fragmentizer.fragmentize() {
-			match fragment.kind {
-				// TODO: somehow split sentence
-				FragmentKind::Sentence => {
-					let mut toks = self.split_sentence(fragment.span);
-					tokens.append(&mut toks);
+	/// Splits fragments into tokens, text matched by one of `ignore_regexes` is
+	/// cut out of fragments beforehand
+	pub(crate) fn process(&self, ignore_regexes: &[Regex]) -> Vec<TokenizedFragment> {
+		let fragments = self.fragmentizer.fragmentize();
+		let directives = Directives::parse(
+			self.source,
+			&self.fragmentizer.comments(&fragments),
+			self.normalization,
+		);
+		let ignored = self.matches(ignore_regexes);
+
+		fragments
+			.into_iter()
+			.map(|fragment| {
+				let mut tokens = Vec::new();
+				for span in unmasked(fragment.span, &ignored) {
+					tokens.append(&mut match fragment.kind {
+						// TODO: somehow split sentence
+						FragmentKind::Sentence | FragmentKind::Comment => self.split_sentence(span),
+
+						// Unknown is parsed as indent
+						FragmentKind::Ident | FragmentKind::Unknown => {
+							self.split_generic_casing(span)
+						}
+					});
 				}
+				tokens.retain(|token| {
+					!directives.ignores(token.span)
+						&& !directives.ignores_word(
+							&self
+								.normalization
+								.apply(&self.source.str_from(token.span).to_string()),
+						)
+				});
 
-				// Unknown is parsed as indent
-				FragmentKind::Ident | FragmentKind::Unknown => {
-					let mut toks = self.split_generic_casing(fragment.span);
-					tokens.append(&mut toks);
+				let selected = directives.language_at(fragment.span.low);
+				let detected = selected.is_none().then(|| self.detect(&fragment)).flatten();
+				let language = selected.or(detected).map(ToOwned::to_owned);
+				let unchecked = ignored
+					.iter()
+					.chain(directives.ignored())
+					.filter(|span| span.low < fragment.span.high && fragment.span.low < span.high)
+					.copied()
+					.collect();
+				TokenizedFragment {
+					fragment,
+					tokens,
+					language,
+					detected: detected.is_some(),
+					unchecked,
 				}
-			}
+			})
+			.collect()
+	}
+
+	/// Parts of the source matched by one of `patterns`, in order
+	fn matches(&self, patterns: &[Regex]) -> Vec<Span> {
+		if patterns.is_empty() {
+			return Vec::new();
+		}
+
+		let source = self.source.0.to_string();
+		let mut matches = patterns
+			.iter()
+			.flat_map(|pattern| pattern.find_iter(&source))
+			.map(|found| Span::new(BytePos::from(found.start()), BytePos::from(found.end())))
+			.collect::<Vec<_>>();
+		matches.sort_by_key(|span| span.low);
+		matches
+	}
+
+	/// Language of a prose fragment, if it is long enough to be identified
+	fn detect(&self, fragment: &Fragment) -> Option<&'static str> {
+		if !self.detect_language || !fragment.kind.is_prose() {
+			return None;
 		}
-		tokens
+		detection::detect(&self.source.str_from(fragment.span).to_string())
 	}
 
-	/// Split code idents on casing boundaires to retrieve individual words
+	/// Split code idents on casing and digit boundaires to retrieve individual words
 	#[must_use]
 	fn split_generic_casing(&self, span: Span) -> Vec<Token> {
 		#[derive(Clone, Copy, PartialEq)]
@@ -101,7 +190,7 @@ impl<'a> FragmentProcessor<'a> {
 		let mut first_word = true;
 		let mut parts_of_fragment = Vec::new();
 
-		for (index, word) in str_split_indices(&source, |c: char| !c.is_alphanumeric()) {
+		for (index, word) in str_split_indices(&source, |c| !normalize::is_word_char(c)) {
 			let local_span = span.relative(BytePos::from(index), BytePos::from(index + word.len()));
 
 			let mut char_indices = word.char_indices().peekable();
@@ -120,9 +209,20 @@ impl<'a> FragmentProcessor<'a> {
 						mode
 					};
 
+					// Word boundary after if current is a digit and next is not,
+					// or the other way around
+					if c.is_numeric() != next.is_numeric() {
+						parts_of_fragment.push(Token {
+							kind: part_kind(&word[init..next_i]),
+							span: local_span.relative(BytePos::from(init), BytePos::from(next_i)),
+						});
+						first_word = false;
+						init = next_i;
+						mode = WordMode::Boundary;
+
 					// Word boundary after if current is not uppercase and next
 					// is uppercase
-					if next_mode == WordMode::Lowercase && next.is_uppercase() {
+					} else if next_mode == WordMode::Lowercase && next.is_uppercase() {
 						parts_of_fragment.push(Token {
 							kind: TokenKind::Word,
 							span: local_span.relative(BytePos::from(init), BytePos::from(next_i)),
@@ -155,7 +255,7 @@ impl<'a> FragmentProcessor<'a> {
 						first_word = false;
 					}
 					parts_of_fragment.push(Token {
-						kind: TokenKind::Word,
+						kind: part_kind(&word[init..]),
 						span: Span::new(local_span.low + BytePos::from(init), local_span.high),
 					});
 					break;
@@ -167,28 +267,126 @@ impl<'a> FragmentProcessor<'a> {
 	}
 
 	/// Splits content by whitespace and trim individual words from non-alphabetical characters
+	///
+	/// Numeric words (e.g. `42`, `3rd`, `v0.20` or `10ms`) are kept whole and
+	/// marked as [`TokenKind::Number`].
 	#[must_use]
 	fn split_sentence(&self, span: Span) -> Vec<Token> {
 		let source = self.source.str_from(span).to_string();
 
 		str_split_indices(&source, char::is_whitespace)
-			.map(|(index, string)| {
-				let offset_before_trim = addr_of(string);
-				let trimmed = string.trim_matches(|c: char| !c.is_alphabetic());
-				let offset = addr_of(trimmed) - offset_before_trim;
-
-				Token {
-					kind: TokenKind::Word,
-					span: span.relative(
-						BytePos::from(index + offset),
-						BytePos::from(index + trimmed.len()),
-					),
-				}
+			.flat_map(|(index, string)| {
+				// Versions keep their hyphen, other words are checked part by part,
+				// e.g. `1st` and `class` in `1st-class`
+				let parts = if is_numeric_word(trim_number(string)) {
+					vec![(index, string)]
+				} else {
+					str_split_indices(string, |c| c == '-')
+						.map(|(offset, part)| (index + offset, part))
+						.collect()
+				};
+
+				parts.into_iter().map(|(index, part)| {
+					let offset_before_trim = addr_of(part);
+
+					let numeric = trim_number(part);
+					let (kind, trimmed) = if is_numeric_word(numeric) {
+						(TokenKind::Number, numeric)
+					} else {
+						let word = part
+							.trim_matches(|c: char| !(c.is_alphabetic() || is_combining_mark(c)));
+						(TokenKind::Word, word)
+					};
+					let offset = addr_of(trimmed) - offset_before_trim;
+
+					Token {
+						kind,
+						span: span.relative(
+							BytePos::from(index + offset),
+							BytePos::from(index + offset + trimmed.len()),
+						),
+					}
+				})
 			})
 			.collect()
 	}
 }
 
+/// Parts of `span` outside of the `masks`, which are sorted
+fn unmasked(span: Span, masks: &[Span]) -> Vec<Span> {
+	let mut parts = Vec::new();
+	let mut low = span.low;
+	for mask in masks
+		.iter()
+		.filter(|mask| mask.low < span.high && span.low < mask.high)
+	{
+		if low < mask.low {
+			parts.push(Span::new(low, mask.low));
+		}
+		low = low.max(mask.high);
+	}
+	if low < span.high {
+		parts.push(Span::new(low, span.high));
+	}
+	parts
+}
+
+/// Kind of an ident part, which is either fully numeric or contains no digit at all
+fn part_kind(part: &str) -> TokenKind {
+	if part.chars().all(char::is_numeric) {
+		TokenKind::Number
+	} else {
+		TokenKind::Word
+	}
+}
+
+/// Units, ordinal suffixes and multipliers allowed right after a number
+const NUMBER_SUFFIXES: [&str; 44] = [
+	"st", "nd", "rd", "th", "x", "k", "m", "g", "b", "kb", "mb", "gb", "tb", "kib", "mib", "gib",
+	"tib", "bit", "bits", "ns", "us", "µs", "ms", "s", "sec", "secs", "min", "mins", "h", "hr",
+	"hrs", "d", "px", "em", "rem", "pt", "dpi", "hz", "khz", "mhz", "ghz", "fps", "kbps", "mbps",
+];
+
+/// Whether a prose word is a number (`42`, `1,000`, `3.14`, `0xff`), an ordinal
+/// (`1st`, `3rd`), a version (`v0.20`, `1.2.3-rc1`) or a quantity with a unit
+/// (`10ms`, `4GiB`)
+///
+/// Other suffixes are not numeric, so that typos like `10milisecond` are checked.
+fn is_numeric_word(word: &str) -> bool {
+	// Versions are often prefixed with a `v`
+	let word = match word.strip_prefix(['v', 'V']) {
+		Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+		_ => word,
+	};
+
+	if !word.starts_with(|c: char| c.is_ascii_digit()) {
+		return false;
+	}
+
+	if let Some(hex) = word.strip_prefix("0x") {
+		return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit() || c == '_');
+	}
+
+	let number_len = word
+		.find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | ',' | '_')))
+		.unwrap_or(word.len());
+	let (number, suffix) = word.split_at(number_len);
+
+	// Pre-release tags and build metadata of versions, e.g. `1.0.0-beta`
+	if number.contains('.') && suffix.starts_with(['-', '+']) {
+		return suffix[1..]
+			.chars()
+			.all(|c| c.is_alphanumeric() || matches!(c, '-' | '.' | '+'));
+	}
+
+	suffix.is_empty() || NUMBER_SUFFIXES.contains(&suffix.to_lowercase().as_str())
+}
+
+/// `word` without the punctuation around it
+fn trim_number(word: &str) -> &str {
+	word.trim_matches(|c| !normalize::is_word_char(c))
+}
+
 fn addr_of(s: &str) -> usize {
 	s.as_ptr() as usize
 }
@@ -213,7 +411,7 @@ mod tests {
 			let bye_jello = true;
 		";
 		let src = Source::new(source);
-		let proc = FragmentProcessor::from_lang("rust", &src);
+		let proc = FragmentProcessor::from_lang("rust", &src, &Options::default());
 
 		let ident_span = Span::new(BytePos(8), BytePos(8 + 9));
 		assert_eq!(
@@ -226,4 +424,165 @@ mod tests {
 			]
 		);
 	}
+
+	#[test]
+	fn split_ident_on_digits() {
+		let source = "
+			let utf8ToString = x86_64;
+		";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("rust", &src, &Options::default());
+
+		let ident_span = Span::new(BytePos(8), BytePos(8 + 12));
+		assert_eq!(
+			proc.split_generic_casing(ident_span),
+			[
+				// utf
+				Token::new_word(ident_span.relative(BytePos(0), BytePos(3))),
+				// 8
+				Token::new(
+					TokenKind::Number,
+					ident_span.relative(BytePos(3), BytePos(4))
+				),
+				// To
+				Token::new_word(ident_span.relative(BytePos(4), BytePos(6))),
+				// String
+				Token::new_word(ident_span.relative(BytePos(6), BytePos(12))),
+			]
+		);
+
+		let ident_span = Span::new(BytePos(23), BytePos(23 + 6));
+		assert_eq!(
+			proc.split_generic_casing(ident_span),
+			[
+				// x
+				Token::new_word(ident_span.relative(BytePos(0), BytePos(1))),
+				// 86
+				Token::new(
+					TokenKind::Number,
+					ident_span.relative(BytePos(1), BytePos(3))
+				),
+				// 64
+				Token::new(
+					TokenKind::Number,
+					ident_span.relative(BytePos(4), BytePos(6))
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn split_sentence_skips_numbers() {
+		let source = "the 3rd run took 10ms (since v0.20)";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("plaintext", &src, &Options::default());
+
+		let span = Span::new(BytePos(0), BytePos::from(source.len()));
+		let kinds = proc
+			.split_sentence(span)
+			.into_iter()
+			.map(|token| (token.kind, src.str_from(token.span).to_string()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			kinds,
+			[
+				(TokenKind::Word, "the".into()),
+				(TokenKind::Number, "3rd".into()),
+				(TokenKind::Word, "run".into()),
+				(TokenKind::Word, "took".into()),
+				(TokenKind::Number, "10ms".into()),
+				(TokenKind::Word, "since".into()),
+				(TokenKind::Number, "v0.20".into()),
+			]
+		);
+	}
+
+	#[test]
+	fn split_sentence_checks_unknown_suffixes() {
+		let source = "a 10milisecond wait, 1st-clas 4GiB 0xff 1.2.3-rc1";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("plaintext", &src, &Options::default());
+
+		let span = Span::new(BytePos(0), BytePos::from(source.len()));
+		let kinds = proc
+			.split_sentence(span)
+			.into_iter()
+			.map(|token| (token.kind, src.str_from(token.span).to_string()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			kinds,
+			[
+				(TokenKind::Word, "a".into()),
+				(TokenKind::Word, "milisecond".into()),
+				(TokenKind::Word, "wait".into()),
+				(TokenKind::Number, "1st".into()),
+				(TokenKind::Word, "clas".into()),
+				(TokenKind::Number, "4GiB".into()),
+				(TokenKind::Number, "0xff".into()),
+				(TokenKind::Number, "1.2.3-rc1".into()),
+			]
+		);
+	}
+
+	#[test]
+	fn split_sentence_spans_trimmed_words() {
+		let source = "(see) \"quoted\",";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("plaintext", &src, &Options::default());
+
+		let span = Span::new(BytePos(0), BytePos::from(source.len()));
+		let spans = proc
+			.split_sentence(span)
+			.into_iter()
+			.map(|token| token.span)
+			.collect::<Vec<_>>();
+
+		// Punctuation before a word used to shift the end of its span
+		assert_eq!(
+			spans,
+			[
+				Span::new(BytePos(1), BytePos(4)),
+				Span::new(BytePos(7), BytePos(13)),
+			]
+		);
+	}
+
+	#[test]
+	fn ignore_regexes_before_splitting() {
+		let source = "see PROJ-1234 and TODO(alice): fix wrod";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("plaintext", &src, &Options::default());
+		let patterns = [
+			Regex::new(r"PROJ-\d+").unwrap(),
+			Regex::new(r"TODO\(\w+\)").unwrap(),
+		];
+
+		let words = proc
+			.process(&patterns)
+			.into_iter()
+			.flat_map(|fragment| fragment.tokens)
+			.map(|token| src.str_from(token.span).to_string())
+			.collect::<Vec<_>>();
+
+		assert_eq!(words, ["see", "and", "fix", "wrod"]);
+	}
+
+	#[test]
+	fn keep_combining_marks_in_words() {
+		let source = "// cre\u{300}me\nlet cafe\u{301}_au_lait = 1;\n";
+		let src = Source::new(source);
+		let proc = FragmentProcessor::from_lang("rust", &src, &Options::default());
+
+		let words = proc
+			.process(&[])
+			.into_iter()
+			.flat_map(|fragment| fragment.tokens)
+			.map(|token| src.str_from(token.span).to_string())
+			.filter(|word| !word.is_empty())
+			.collect::<Vec<_>>();
+
+		assert_eq!(words, ["cre\u{300}me", "cafe\u{301}", "au", "lait"]);
+	}
 }
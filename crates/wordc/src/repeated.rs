@@ -0,0 +1,121 @@
+//! Words written twice in a row, e.g. "the the", which a single-word checker never finds
+
+use crate::{
+	checker::{DiagnosticKind, WordDiagnostic},
+	directive,
+	processor::{Token, TokenKind, TokenizedFragment},
+	span::{BytePos, Source, Span},
+};
+
+/// Words that are correctly repeated, e.g. "that that is"
+const ALLOWED: [&str; 2] = ["had", "that"];
+
+/// Words of prose fragments that are the same as the word before them
+pub(crate) fn find(source: &Source, fragments: &[TokenizedFragment]) -> Vec<WordDiagnostic> {
+	let mut words = fragments
+		.iter()
+		.filter(|fragment| fragment.fragment.kind.is_prose())
+		.flat_map(|fragment| &fragment.tokens)
+		// Comment markers are trimmed down to empty tokens
+		.filter(|token| token.span.low < token.span.high)
+		.map(|token| (token, source.str_from(token.span).to_string()))
+		.collect::<Vec<(&Token, String)>>();
+	words.sort_by_key(|(token, _)| token.span.low);
+	// Doc comments may be captured as several fragments
+	words.dedup_by_key(|(token, _)| token.span);
+
+	words
+		.windows(2)
+		.filter_map(|pair| {
+			let [(previous, previous_word), (token, word)] = pair else {
+				return None;
+			};
+			let lowercase = word.to_lowercase();
+			let repeated = previous.kind == TokenKind::Word
+				&& token.kind == TokenKind::Word
+				&& previous_word.to_lowercase() == lowercase
+				&& !ALLOWED.contains(&lowercase.as_str());
+			let gap = source
+				.str_from(Span::new(previous.span.high, token.span.low))
+				.to_string();
+
+			(repeated && is_separator(&gap)).then(|| WordDiagnostic {
+				word: word.clone(),
+				span: token.span,
+				kind: DiagnosticKind::Repeated {
+					removal: removal(source, token.span, &gap),
+				},
+				ident: None,
+			})
+		})
+		.collect()
+}
+
+/// The repeated word at `span` along with the spaces before it, or after it
+/// when it starts a line, so that the separator of the words is kept
+fn removal(source: &Source, span: Span, gap: &str) -> Span {
+	let is_space = |c: &char| *c == ' ' || *c == '\t';
+
+	let before = gap.chars().rev().take_while(is_space).count();
+	if before > 0 {
+		return Span::new(span.low - BytePos::from(before), span.high);
+	}
+
+	let after = source
+		.0
+		.byte_slice(span.high.to_usize()..)
+		.chars()
+		.take_while(is_space)
+		.count();
+	Span::new(span.low, span.high + BytePos::from(after))
+}
+
+/// Whether `gap` only holds spaces, or a line break and the markers of the
+/// following comment line
+fn is_separator(gap: &str) -> bool {
+	match gap.matches('\n').count() {
+		0 => !gap.is_empty() && gap.chars().all(char::is_whitespace),
+		1 => gap
+			.chars()
+			.all(|c| c.is_whitespace() || directive::is_marker_char(c)),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{checker::Options, processor::FragmentProcessor};
+
+	#[test]
+	fn find_repeated_words() {
+		let source = Source::new(concat!(
+			"/// Checks the the source, this is\n",
+			"/// is fine. That that is, is.\n",
+			"fn the_the() { \"a a\"; \"the\nthe end\"; }\n",
+		));
+		let processor = FragmentProcessor::from_lang("rust", &source, &Options::default());
+		let repeated = find(&source, &processor.process(&[]))
+			.into_iter()
+			.map(|diag| {
+				let DiagnosticKind::Repeated { removal } = diag.kind else {
+					unreachable!()
+				};
+				(diag.word, source.str_from(removal).to_string())
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			repeated,
+			[
+				("the".to_owned(), " the".to_owned()),
+				("is".to_owned(), " is".to_owned()),
+				("a".to_owned(), " a".to_owned()),
+				("the".to_owned(), "the ".to_owned()),
+			]
+		);
+		assert!(is_separator(" \n  * "));
+		assert!(!is_separator(", "));
+		assert!(!is_separator("\n\n"));
+	}
+}
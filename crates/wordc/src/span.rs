@@ -22,7 +22,7 @@ impl Source {
 	#[track_caller]
 	pub fn str_from(&self, span: Span) -> RopeSlice<'_> {
 		self.0
-			.get_slice(span.low.to_usize()..span.high.to_usize())
+			.get_byte_slice(span.low.to_usize()..span.high.to_usize())
 			.unwrap()
 	}
 
@@ -30,7 +30,7 @@ impl Source {
 	pub fn to_line_col(&self, offset: BytePos) -> Option<Position> {
 		let line = self.0.try_byte_to_line(offset.to_usize()).ok()?;
 		let first_char_of_line = self.0.try_line_to_char(line).ok()?;
-		let column = offset.to_usize() - first_char_of_line;
+		let column = self.0.try_byte_to_char(offset.to_usize()).ok()? - first_char_of_line;
 		Some(Position(line as u32, column as u32))
 	}
 
@@ -222,3 +222,20 @@ impl_pos! {
 	#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 	pub struct CharPos(pub usize);
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn multibyte_offsets() {
+		let source = Source::new("déjà vu\nnaïve café");
+
+		let cafe = Span::new(BytePos(17), BytePos(22));
+		assert_eq!(source.str_from(cafe), "café");
+
+		let Range(start, end) = source.span_to_range(cafe).unwrap();
+		assert_eq!((start.0, start.1), (1, 6));
+		assert_eq!((end.0, end.1), (1, 10));
+	}
+}
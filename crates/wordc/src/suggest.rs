@@ -0,0 +1,55 @@
+//! Suggestions of correctly spelled words close to a misspelled one
+
+/// Maximum number of suggestions returned by a backend
+pub(crate) const MAX_SUGGESTIONS: usize = 5;
+
+/// Words of `candidates` at most two edits away from `word`, closest first
+pub(crate) fn closest_words<'c>(
+	word: &str,
+	candidates: impl Iterator<Item = &'c String>,
+) -> Vec<String> {
+	let word = word.to_lowercase();
+
+	let mut scored = candidates
+		.filter_map(|candidate| {
+			let distance = edit_distance(&word, &candidate.to_lowercase());
+			(distance <= 2).then_some((distance, candidate))
+		})
+		.collect::<Vec<_>>();
+	scored.sort_unstable();
+
+	scored
+		.into_iter()
+		.take(MAX_SUGGESTIONS)
+		.map(|(_, candidate)| candidate.clone())
+		.collect()
+}
+
+/// Levenshtein distance between two words, counting a swap of adjacent letters as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+	let a = a.chars().collect::<Vec<_>>();
+	let b = b.chars().collect::<Vec<_>>();
+
+	// Rows of the distance matrix, two rows back are needed for swaps
+	let mut before = vec![0; b.len() + 1];
+	let mut previous = (0..=b.len()).collect::<Vec<_>>();
+	let mut current = vec![0; b.len() + 1];
+
+	for i in 1..=a.len() {
+		current[0] = i;
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			current[j] = (previous[j] + 1)
+				.min(current[j - 1] + 1)
+				.min(previous[j - 1] + cost);
+
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				current[j] = current[j].min(before[j - 2] + 1);
+			}
+		}
+		before.clone_from(&previous);
+		previous.clone_from(&current);
+	}
+
+	previous[b.len()]
+}
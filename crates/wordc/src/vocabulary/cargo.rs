@@ -0,0 +1,152 @@
+//! Crate names declared by Cargo manifests and lockfiles
+
+use toml::{Table, Value};
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Package and dependency names of a `Cargo.toml` manifest
+///
+/// # Errors
+///
+/// Returns an error if the manifest is not valid TOML
+pub fn manifest_words(manifest: &str) -> Result<Vec<String>, toml::de::Error> {
+	let manifest = manifest.parse::<Table>()?;
+	Ok(manifest_names(&manifest, true)
+		.into_iter()
+		.flat_map(crate_name_words)
+		.collect())
+}
+
+/// Names the package and its dependencies are referred to in code, e.g.
+/// `serde_json`
+///
+/// # Errors
+///
+/// Returns an error if the manifest is not valid TOML
+pub fn manifest_crates(manifest: &str) -> Result<Vec<String>, toml::de::Error> {
+	let manifest = manifest.parse::<Table>()?;
+	Ok(manifest_names(&manifest, false)
+		.into_iter()
+		.map(|name| name.replace('-', "_"))
+		.collect())
+}
+
+/// Package and dependency names, along with the package name of renamed
+/// dependencies if `packages` is set
+fn manifest_names(manifest: &Table, packages: bool) -> Vec<&str> {
+	let mut names = Vec::new();
+
+	if let Some(name) = manifest
+		.get("package")
+		.and_then(|package| package.get("name"))
+		.and_then(Value::as_str)
+	{
+		names.push(name);
+	}
+
+	// Dependencies can be declared at the root, for the whole workspace or
+	// for specific targets
+	let mut tables = vec![manifest];
+	tables.extend(manifest.get("workspace").and_then(Value::as_table));
+	tables.extend(
+		manifest
+			.get("target")
+			.and_then(Value::as_table)
+			.into_iter()
+			.flat_map(|targets| targets.values().filter_map(Value::as_table)),
+	);
+
+	let dependencies = tables.into_iter().flat_map(|table| {
+		DEPENDENCY_TABLES
+			.iter()
+			.filter_map(|key| table.get(*key)?.as_table())
+			.flatten()
+	});
+	for (name, dependency) in dependencies {
+		names.push(name);
+
+		// Renamed dependencies, e.g. `real = { package = "real-name" }`
+		if let Some(package) = dependency
+			.get("package")
+			.and_then(Value::as_str)
+			.filter(|_| packages)
+		{
+			names.push(package);
+		}
+	}
+
+	names
+}
+
+/// Names of every package locked in a `Cargo.lock`
+///
+/// # Errors
+///
+/// Returns an error if the lockfile is not valid TOML
+pub fn lockfile_words(lockfile: &str) -> Result<Vec<String>, toml::de::Error> {
+	let lockfile = lockfile.parse::<Table>()?;
+
+	let names = lockfile
+		.get("package")
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter_map(|package| package.get("name")?.as_str());
+
+	Ok(names.flat_map(crate_name_words).collect())
+}
+
+/// A crate name, its snake case and kebab case spellings, and its parts
+fn crate_name_words(name: &str) -> Vec<String> {
+	let mut words = vec![
+		name.to_owned(),
+		name.replace('-', "_"),
+		name.replace('_', "-"),
+	];
+	words.extend(
+		name.split(['-', '_'])
+			.filter(|part| !part.is_empty())
+			.map(ToOwned::to_owned),
+	);
+	words
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn collect_manifest_names() {
+		let manifest = r#"
+			[package]
+			name = "lspelling-lsp"
+
+			[dependencies]
+			tokio = { version = "1", features = ["full"] }
+			json = { package = "serde_json", version = "1" }
+
+			[workspace.dependencies]
+			ropey = "1"
+		"#;
+
+		let words = manifest_words(manifest).unwrap();
+		for word in [
+			"lspelling-lsp",
+			"lspelling_lsp",
+			"lspelling",
+			"lsp",
+			"tokio",
+			"serde_json",
+			"serde-json",
+			"serde",
+			"ropey",
+		] {
+			assert!(words.iter().any(|w| w == word), "missing `{word}`");
+		}
+
+		assert_eq!(
+			manifest_crates(manifest).unwrap(),
+			["lspelling_lsp", "json", "tokio", "ropey"]
+		);
+	}
+}
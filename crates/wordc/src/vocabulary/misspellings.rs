@@ -0,0 +1,40 @@
+//! Frequent misspellings with a single possible correction, e.g. `teh` for `the`
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use crate::casing;
+
+const MISSPELLINGS: &str = include_str!("../../vocabulary/misspellings.txt");
+
+static CORRECTIONS: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| parse(MISSPELLINGS));
+
+/// Reads lines in the codespell format, `typo->correction`
+fn parse(content: &str) -> HashMap<&str, &str> {
+	content
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.filter_map(|line| line.split_once("->"))
+		.map(|(typo, correction)| (typo.trim(), correction.trim()))
+		.collect()
+}
+
+/// Correction of `word` if it is a common misspelling, in the case of `word`
+#[must_use]
+pub fn correction(word: &str) -> Option<String> {
+	let correction = CORRECTIONS.get(word.to_lowercase().as_str())?;
+	Some(casing::match_case(word, correction))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn correct_common_misspellings() {
+		assert_eq!(correction("teh").as_deref(), Some("the"));
+		assert_eq!(correction("Recieve").as_deref(), Some("Receive"));
+		assert_eq!(correction("SEPERATE").as_deref(), Some("SEPARATE"));
+		assert_eq!(correction("the"), None);
+	}
+}
@@ -0,0 +1,30 @@
+//! Bundled technical word lists and project vocabulary
+
+use std::sync::{Arc, LazyLock};
+
+use crate::wordlist::WordList;
+
+pub mod cargo;
+pub mod misspellings;
+
+const PROGRAMMING_WORDS: &str = include_str!("../../vocabulary/programming.txt");
+const RUST_WORDS: &str = include_str!("../../vocabulary/rust.txt");
+
+static PROGRAMMING: LazyLock<Arc<WordList>> =
+	LazyLock::new(|| Arc::new(WordList::parse(PROGRAMMING_WORDS)));
+static RUST: LazyLock<Arc<WordList>> = LazyLock::new(|| Arc::new(WordList::parse(RUST_WORDS)));
+
+/// General programming terms, shared by every language
+#[must_use]
+pub fn programming() -> Arc<WordList> {
+	Arc::clone(&PROGRAMMING)
+}
+
+/// Keywords and standard library names of a fragmentizer language
+#[must_use]
+pub fn language(lang_code: &str) -> Option<Arc<WordList>> {
+	match lang_code {
+		"rust" => Some(Arc::clone(&RUST)),
+		_ => None,
+	}
+}
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use crate::suggest::closest_words;
+
+/// A plain set of accepted words
+///
+/// Lookups are case-insensitive for words listed in lowercase, so that `json`
+/// also accepts `Json` and `JSON`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordList {
+	words: HashSet<String>,
+	/// Lowercased words, for case-insensitive lookups
+	folded: HashSet<String>,
+}
+
+impl WordList {
+	/// Parses a list with one word per line, lines starting with `#` are comments
+	#[must_use]
+	pub fn parse(content: &str) -> Self {
+		content
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(ToOwned::to_owned)
+			.collect()
+	}
+
+	#[must_use]
+	pub fn contains(&self, word: &str) -> bool {
+		self.words.contains(word) || self.words.contains(&word.to_lowercase())
+	}
+
+	/// Whether `word` is listed in any case, e.g. `github` matches `GitHub`
+	#[must_use]
+	pub fn contains_folded(&self, word: &str) -> bool {
+		self.folded.contains(&word.to_lowercase())
+	}
+
+	pub fn insert(&mut self, word: impl Into<String>) {
+		let word = word.into();
+		self.folded.insert(word.to_lowercase());
+		self.words.insert(word);
+	}
+
+	/// Listed words close to `word`, closest first
+	#[must_use]
+	pub fn suggest(&self, word: &str) -> Vec<String> {
+		closest_words(word, self.words.iter())
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.words.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.words.is_empty()
+	}
+}
+
+impl FromIterator<String> for WordList {
+	fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+		let mut list = Self::default();
+		list.extend(iter);
+		list
+	}
+}
+
+impl Extend<String> for WordList {
+	fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
+		for word in iter {
+			self.insert(word);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_and_lookup() {
+		let list = WordList::parse("# comment\njson\n\nGitHub\n");
+
+		assert_eq!(list.len(), 2);
+		assert!(list.contains("json"));
+		assert!(list.contains("JSON"));
+		assert!(list.contains("GitHub"));
+		assert!(!list.contains("github"));
+		assert!(list.contains_folded("github"));
+		assert!(!list.contains("comment"));
+	}
+}